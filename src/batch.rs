@@ -0,0 +1,46 @@
+use std::io::BufRead;
+
+use crate::{error_message, solve, Format, Options};
+
+// Solve every line of the input on its own, an invalid line does not stop the others
+// Empty lines and lines starting with '#' are skipped
+// In JSON and LaTeX formats each line only gives its object or its formulas,
+// the LaTeX errors are written on the error output
+// Returns the number of lines that failed
+pub fn run(input: impl BufRead, options: &Options) -> usize
+{
+	let (mut solved, mut failed) = (0, 0);
+	let text = options.format == Format::Text;
+	for (i, line) in input.lines().enumerate()
+	{
+		let line = match line
+		{
+			Ok(line) => line,
+			Err(e) =>
+			{
+				eprintln!("Error: cannot read line {}: {}", i + 1, e);
+				failed += 1;
+				break
+			}
+		};
+		let expression = line.trim();
+		if expression.is_empty() || expression.starts_with('#') { continue }
+		if text { println!("[line {}] {}", i + 1, expression) }
+		match solve(expression, options)
+		{
+			Ok(_) => solved += 1,
+			Err(e) =>
+			{
+				match options.format
+				{
+					Format::Latex => eprintln!("{}", error_message(&e, expression, options)),
+					_ => println!("{}", error_message(&e, expression, options))
+				}
+				failed += 1;
+			}
+		}
+		if text { println!() }
+	}
+	if text { println!("{} solved, {} failed", solved, failed) }
+	failed
+}
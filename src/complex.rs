@@ -0,0 +1,192 @@
+use std::fmt;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+use crate::{abs, format_number, latex_number, Latex};
+
+// A part smaller than this fraction of the modulus is displayed as null, it is the rounding noise of a computation
+const DISPLAY_THRESHOLD: f64 = 1e-10;
+
+// A complex number re + i * im
+// ex:	Complex { re: -1.0, im: 2.0 } => -1 + 2i
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex
+{
+	pub re: f64,
+	pub im: f64
+}
+
+impl Complex
+{
+	pub fn new(re: f64, im: f64) -> Self
+	{
+		Complex { re, im }
+	}
+
+	pub fn real(re: f64) -> Self
+	{
+		Complex { re, im: 0.0 }
+	}
+
+	pub fn is_real(&self) -> bool
+	{
+		self.im == 0.0
+	}
+
+	pub fn modulus(&self) -> f64
+	{
+		self.re.hypot(self.im)
+	}
+
+	pub fn conjugate(&self) -> Complex
+	{
+		Complex::new(self.re, -self.im)
+	}
+
+	pub fn is_finite(&self) -> bool
+	{
+		self.re.is_finite() && self.im.is_finite()
+	}
+
+	// The principal square root, the other one is its opposite
+	// ex: √(-4) => 2i
+	pub fn sqrt(&self) -> Complex
+	{
+		let modulus = self.modulus();
+		let re = ((modulus + self.re) / 2.0).sqrt();
+		let im = ((modulus - self.re) / 2.0).sqrt();
+		Complex::new(re, if self.im < 0.0 { -im } else { im })
+	}
+}
+
+impl From<f64> for Complex
+{
+	fn from(re: f64) -> Self
+	{
+		Complex::real(re)
+	}
+}
+
+impl Add for Complex
+{
+	type Output = Complex;
+
+	fn add(self, other: Complex) -> Complex
+	{
+		Complex::new(self.re + other.re, self.im + other.im)
+	}
+}
+
+impl Sub for Complex
+{
+	type Output = Complex;
+
+	fn sub(self, other: Complex) -> Complex
+	{
+		Complex::new(self.re - other.re, self.im - other.im)
+	}
+}
+
+impl Mul for Complex
+{
+	type Output = Complex;
+
+	// (a + bi)(c + di) = ac - bd + (ad + bc)i
+	fn mul(self, other: Complex) -> Complex
+	{
+		Complex::new(self.re * other.re - self.im * other.im, self.re * other.im + self.im * other.re)
+	}
+}
+
+impl Div for Complex
+{
+	type Output = Complex;
+
+	// (a + bi) / (c + di) = (a + bi)(c - di) / (c² + d²), computed with the ratio of c and d (Smith's method)
+	// so that c² + d² can't overflow. ex: a divisor of modulus 1e200
+	fn div(self, other: Complex) -> Complex
+	{
+		let (a, b, c, d) = (self.re, self.im, other.re, other.im);
+		match abs(c) >= abs(d)
+		{
+			true =>
+			{
+				let (r, den) = (d / c, c + d * (d / c));
+				Complex::new((a + b * r) / den, (b - a * r) / den)
+			}
+			false =>
+			{
+				let (r, den) = (c / d, c * (c / d) + d);
+				Complex::new((a * r + b) / den, (b * r - a) / den)
+			}
+		}
+	}
+}
+
+impl Neg for Complex
+{
+	type Output = Complex;
+
+	fn neg(self) -> Complex
+	{
+		Complex::new(-self.re, -self.im)
+	}
+}
+
+impl Complex
+{
+	// The parts are written with number, a null part is not written
+	fn write(&self, number: impl Fn(f64) -> String) -> String
+	{
+		let noise = DISPLAY_THRESHOLD * self.modulus();
+		let re = if abs(self.re) < noise { 0.0 } else { self.re };
+		let im = if abs(self.im) < noise { 0.0 } else { self.im };
+		// ex: 0.9999999999999999i is written i
+		let imaginary = match number(abs(im)).as_str()
+		{
+			"1" => "i".to_string(),
+			magnitude => format!("{}i", magnitude)
+		};
+		match (re == 0.0, im == 0.0, im < 0.0)
+		{
+			(_, true, _) => number(re),
+			(true, false, false) => imaginary,
+			(true, false, true) => format!("-{}", imaginary),
+			(false, false, false) => format!("{} + {}", number(re), imaginary),
+			(false, false, true) => format!("{} - {}", number(re), imaginary)
+		}
+	}
+}
+
+impl fmt::Display for Complex
+{
+	// ex: 3 | -2i | 1 + i | -0.50 - 1.20i
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		write!(f, "{}", self.write(format_number))
+	}
+}
+
+impl Latex for Complex
+{
+	fn latex(&self) -> String
+	{
+		self.write(|n| latex_number(&format_number(n)))
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+
+	#[test]
+	fn display()
+	{
+		assert_eq!(Complex::new(0.0, 0.9999999999999999).to_string(), "i");
+		assert_eq!(Complex::new(1.0, -1.0000000000000002).to_string(), "1 - i");
+		assert_eq!(Complex::new(-0.5, 1.2).to_string(), "-0.50 + 1.20i");
+		// The noise is relative to the modulus
+		assert_eq!(Complex::new(2.0, 1e-17).to_string(), "2");
+		assert_eq!(Complex::real(-1e-200).to_string(), "-1.00e-200");
+	}
+}
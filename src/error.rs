@@ -0,0 +1,179 @@
+use std::fmt;
+use std::error::Error;
+
+// A range of characters of the expression, end excluded
+// ex: "2x + y = 0" => Span { start: 5, end: 6 } is 'y'
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span
+{
+	pub start: usize,
+	pub end: usize
+}
+
+impl Span
+{
+	pub fn new(start: usize, end: usize) -> Self
+	{
+		Span { start, end }
+	}
+
+	// The span of a single character
+	pub fn at(pos: usize) -> Self
+	{
+		Span { start: pos, end: pos + 1 }
+	}
+}
+
+// The kinds of syntax errors, the strings are the displayed tokens
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxKind
+{
+	InvalidNumber(String),
+	MisplacedExponent,
+	ExpectedExponent,
+	UnexpectedToken { found: String, after: String },
+	UnexpectedStart(String),
+	UnexpectedEnd(String),
+	ExpectedOperand(String),
+	MultipleComparisons,
+	// The first unknown and the other one
+	MultipleUnknowns(String, String),
+	// In a system of equations
+	NotLinear,
+	ExpectedEqual,
+	SystemComparison(String),
+	ComparisonOperands(String),
+	ComparisonInGroup(String),
+	InvalidDivision(String, String),
+	UnmatchedOpen,
+	UnmatchedClose,
+	EmptyGroup,
+	EmptyExpression
+}
+
+impl fmt::Display for SyntaxKind
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		match self
+		{
+			SyntaxKind::InvalidNumber(n) => write!(f, "'{}' is not a valid number", n),
+			SyntaxKind::MisplacedExponent => write!(f, "'^' is only valid after a number, a variable or ')'"),
+			SyntaxKind::ExpectedExponent => write!(f, "expected a valid exponent after '^'"),
+			SyntaxKind::UnexpectedToken { found, after } => write!(f, "'{}' is not a valid token after '{}'", found, after),
+			SyntaxKind::UnexpectedStart(t) => write!(f, "'{}' cannot be at the beginning of the expression", t),
+			SyntaxKind::UnexpectedEnd(t) => write!(f, "'{}' cannot be at the end of the expression", t),
+			SyntaxKind::ExpectedOperand(t) => write!(f, "expected a variable or number instead of '{}'", t),
+			SyntaxKind::MultipleComparisons => write!(f, "an expression cannot have more than one comparison"),
+			SyntaxKind::MultipleUnknowns(a, b) => write!(f, "'{}' and '{}' cannot both be unknowns of the equation", a, b),
+			SyntaxKind::NotLinear => write!(f, "the equations of a system must be linear"),
+			SyntaxKind::ExpectedEqual => write!(f, "every equation of a system needs a '='"),
+			SyntaxKind::SystemComparison(t) => write!(f, "'{}' cannot be used in a system of equations", t),
+			SyntaxKind::ComparisonOperands(t) => write!(f, "'{}' need to be between two numbers or variables", t),
+			SyntaxKind::ComparisonInGroup(t) => write!(f, "'{}' cannot be inside parentheses", t),
+			SyntaxKind::InvalidDivision(a, b) => write!(f, "can't divide '{}' with '{}'", a, b),
+			SyntaxKind::UnmatchedOpen => write!(f, "'(' has no matching ')'"),
+			SyntaxKind::UnmatchedClose => write!(f, "')' has no matching '('"),
+			SyntaxKind::EmptyGroup => write!(f, "'()' is not a valid expression"),
+			SyntaxKind::EmptyExpression => write!(f, "the expression must not be empty")
+		}
+	}
+}
+
+// Every error the library can return
+// pos and span locate the error in the expression when it is known
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComputorError
+{
+	Lexical { ch: char, pos: usize },
+	Syntax { kind: SyntaxKind, span: Option<Span> },
+	DivisionByZero { dividend: String, span: Option<Span> },
+	DegreeTooHigh { max: u32, span: Option<Span> },
+	InvalidExponent { exponent: String, span: Option<Span> },
+	InfiniteCoefficient
+}
+
+impl ComputorError
+{
+	pub fn syntax(kind: SyntaxKind, span: Option<Span>) -> Self
+	{
+		ComputorError::Syntax { kind, span }
+	}
+
+	// A short name of the variant for the machine readable outputs
+	pub fn kind(&self) -> &'static str
+	{
+		match self
+		{
+			ComputorError::Lexical { .. } => "lexical",
+			ComputorError::Syntax { .. } => "syntax",
+			ComputorError::DivisionByZero { .. } => "division_by_zero",
+			ComputorError::DegreeTooHigh { .. } => "degree_too_high",
+			ComputorError::InvalidExponent { .. } => "invalid_exponent",
+			ComputorError::InfiniteCoefficient => "infinite_coefficient"
+		}
+	}
+
+	// The characters of the expression the error comes from
+	pub fn span(&self) -> Option<Span>
+	{
+		match self
+		{
+			ComputorError::Lexical { pos, .. } => Some(Span::at(*pos)),
+			ComputorError::Syntax { span, .. }
+			| ComputorError::DivisionByZero { span, .. }
+			| ComputorError::DegreeTooHigh { span, .. }
+			| ComputorError::InvalidExponent { span, .. } => *span,
+			ComputorError::InfiniteCoefficient => None
+		}
+	}
+
+	// Locate an error raised by a computation that does not know the expression,
+	// an already known span is kept
+	pub fn with_span(self, at: Span) -> Self
+	{
+		match self
+		{
+			ComputorError::Syntax { kind, span } => ComputorError::Syntax { kind, span: span.or(Some(at)) },
+			ComputorError::DivisionByZero { dividend, span } => ComputorError::DivisionByZero { dividend, span: span.or(Some(at)) },
+			ComputorError::DegreeTooHigh { max, span } => ComputorError::DegreeTooHigh { max, span: span.or(Some(at)) },
+			ComputorError::InvalidExponent { exponent, span } => ComputorError::InvalidExponent { exponent, span: span.or(Some(at)) },
+			error => error
+		}
+	}
+
+	// The error message followed by the expression with the faulty characters underlined
+	// ex:	Lexical error: '@' is not a valid token
+	//		  2x @ 3
+	//		     ^
+	pub fn render(&self, expression: &str) -> String
+	{
+		let span = match self.span()
+		{
+			Some(span) => span,
+			None => return self.to_string()
+		};
+		// Tabs are shown as spaces so that every char takes one column
+		let line: String = expression.chars().map(|c| if c == '\t' { ' ' } else { c }).collect();
+		let width = span.end.max(span.start + 1) - span.start;
+		format!("{}\n  {}\n  {}^{}", self, line, " ".repeat(span.start), "~".repeat(width - 1))
+	}
+}
+
+impl fmt::Display for ComputorError
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		match self
+		{
+			ComputorError::Lexical { ch, .. } => write!(f, "Lexical error: '{}' is not a valid token", ch),
+			ComputorError::Syntax { kind, .. } => write!(f, "Syntax error: {}", kind),
+			ComputorError::DivisionByZero { dividend, .. } => write!(f, "Math error: attempted to divide {} by zero", dividend),
+			ComputorError::DegreeTooHigh { max, .. } => write!(f, "Syntax error: cannot handle a polynomial degree higher than {}", max),
+			ComputorError::InvalidExponent { exponent, .. } => write!(f, "Syntax error: '{}' is not a valid exponent", exponent),
+			ComputorError::InfiniteCoefficient => write!(f, "Math error: the solution can't be found because one of the terms is infinite")
+		}
+	}
+}
+
+impl Error for ComputorError {}
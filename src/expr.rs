@@ -0,0 +1,208 @@
+use crate::{pow, superscript, ComputorError, Polynomial, Scalar};
+use crate::error::{Span, SyntaxKind};
+use crate::polynomial::MAX_DEGREE;
+use crate::steps::{StepLog, Verbosity};
+
+// An arithmetic expression built by the parser, a subtraction is the addition of a negation
+// ex: 2x² - 1 => Add(Mul(Num(2), Pow(Var(0), 2)), Neg(Num(1)))
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr<T = f64>
+{
+	Num(T),
+
+	// The unknown of the given index, an equation has the single unknown 0
+	Var(usize),
+
+	Neg(Box<SpannedExpr<T>>),
+	Add(Box<SpannedExpr<T>>, Box<SpannedExpr<T>>),
+	Mul(Box<SpannedExpr<T>>, Box<SpannedExpr<T>>),
+	Div(Box<SpannedExpr<T>>, Box<SpannedExpr<T>>),
+	Pow(Box<SpannedExpr<T>>, u32)
+}
+
+// An expression with the characters of the expression it was read from
+pub type SpannedExpr<T = f64> = (Expr<T>, Span);
+
+impl<T: Scalar> Expr<T>
+{
+	// How tightly the expression is bound when it is written, a lower one needs parentheses inside a higher one
+	fn precedence(&self) -> u8
+	{
+		match self
+		{
+			Expr::Add(_, _) => 1,
+			Expr::Neg(_) => 2,
+			// ex: 2x is written as a single term
+			Expr::Mul(a, b) if matches!((&a.0, &b.0), (Expr::Num(_), Expr::Var(_) | Expr::Pow(_, _))) => 4,
+			Expr::Mul(_, _) | Expr::Div(_, _) => 3,
+			Expr::Pow(_, _) => 4,
+			Expr::Num(_) | Expr::Var(_) => 5
+		}
+	}
+
+	// The expression written inside an operator of precedence min, with parentheses if needed
+	fn write_within(&self, min: u8, names: &[String]) -> String
+	{
+		match self.precedence() < min
+		{
+			true => format!("({})", self.write(names)),
+			false => self.write(names)
+		}
+	}
+
+	// The opposite of a negative number read by the lexer, or of its product, so that it's written as a subtraction
+	// ex: Num(-3) => Num(3), Mul(Num(-3), Var(0)) => Mul(Num(3), Var(0)), so x - 3x and not x + -3x
+	fn opposite(&self) -> Option<Expr<T>>
+	{
+		match self
+		{
+			Expr::Num(n) if *n < T::zero() => Some(Expr::Num(-*n)),
+			Expr::Mul(a, b) => match a.0
+			{
+				Expr::Num(n) if n < T::zero() => Some(Expr::Mul(Box::new((Expr::Num(-n), a.1)), b.clone())),
+				_ => None
+			},
+			_ => None
+		}
+	}
+
+	// The expression written with the names of its unknowns
+	// ex: 2x / (x + 1)
+	pub fn write(&self, names: &[String]) -> String
+	{
+		let child = |(expr, _): &SpannedExpr<T>, min: u8| expr.write_within(min, names);
+		match self
+		{
+			Expr::Num(n) => n.format(),
+			Expr::Var(i) => names.get(*i).map_or("x".to_string(), |name| name.clone()),
+			Expr::Neg(e) => format!("-{}", child(e, 3)),
+			Expr::Add(a, b) => match (&b.0, b.0.opposite())
+			{
+				(Expr::Neg(b), _) => format!("{} - {}", child(a, 1), child(b, 2)),
+				(_, Some(opposite)) => format!("{} - {}", child(a, 1), opposite.write_within(2, names)),
+				_ => format!("{} + {}", child(a, 1), child(b, 1))
+			},
+			Expr::Mul(a, b) => match (&a.0, &b.0)
+			{
+				// ex: 4.5x²
+				(Expr::Num(_), Expr::Var(_)) | (Expr::Num(_), Expr::Pow(_, _)) => format!("{}{}", child(a, 3), child(b, 4)),
+				_ => format!("{} * {}", child(a, 3), child(b, 4))
+			},
+			Expr::Div(a, b) => format!("{} / {}", child(a, 3), child(b, 4)),
+			Expr::Pow(a, n) => format!("{}{}", child(a, 5), superscript(*n))
+		}
+	}
+}
+
+fn check_degree(degree: u64, span: Span) -> Result<(), ComputorError>
+{
+	if degree > MAX_DEGREE as u64
+	{
+		return Err(ComputorError::DegreeTooHigh { max: MAX_DEGREE, span: Some(span) });
+	}
+	Ok(())
+}
+
+// A division by zero reports the dividend as it was written
+fn division_error<T: Scalar>(error: ComputorError, dividend: &Expr<T>, names: &[String], span: Span) -> ComputorError
+{
+	match error
+	{
+		ComputorError::DivisionByZero { .. } => ComputorError::DivisionByZero { dividend: dividend.write(names), span: Some(span) },
+		error => error.with_span(span)
+	}
+}
+
+// Expand an expression with a single unknown into a polynomial, each product, division and power
+// that changes the expression is recorded in the log
+// ex: (x + 1)² / 2 => 0.50x² + x + 0.50
+pub fn to_polynomial<T: Scalar>((expr, span): &SpannedExpr<T>, names: &[String], log: &mut StepLog) -> Result<Polynomial<T>, ComputorError>
+{
+	let poly = match expr
+	{
+		Expr::Num(n) => Polynomial::term(*n, 0),
+		Expr::Var(_) => Polynomial::term(T::one(), 1),
+		Expr::Neg(e) => to_polynomial(e, names, log)?.scale(-T::one()),
+		Expr::Add(a, b) => to_polynomial(a, names, log)? + to_polynomial(b, names, log)?,
+		Expr::Mul(a, b) =>
+		{
+			let (a, b) = (to_polynomial(a, names, log)?, to_polynomial(b, names, log)?);
+			check_degree(a.degree() as u64 + b.degree() as u64, *span)?;
+			a * b
+		}
+		// The divisor must be a number
+		Expr::Div(a, b) =>
+		{
+			let divisor = to_polynomial(b, names, log)?;
+			if !divisor.is_constant()
+			{
+				let kind = SyntaxKind::InvalidDivision(a.0.write_within(3, names), b.0.write_within(4, names));
+				return Err(ComputorError::syntax(kind, Some(*span)));
+			}
+			to_polynomial(a, names, log)?.divide(divisor.coefficient(0)).map_err(|e| division_error(e, &a.0, names, *span))?
+		}
+		Expr::Pow(a, n) =>
+		{
+			let base = to_polynomial(a, names, log)?;
+			check_degree(base.degree() as u64 * *n as u64, *span)?;
+			// The exponent of a number is not bounded by the degree, ex: 2^4000000000
+			match base.is_constant()
+			{
+				true => Polynomial::term(pow(base.coefficient(0), *n), 0),
+				false => (0..*n).fold(Polynomial::term(T::one(), 0), |poly, _| poly * base.clone())
+			}
+		}
+	};
+
+	// ex: (x + 1)² = x² + 2x + 1
+	if matches!(expr, Expr::Mul(_, _) | Expr::Div(_, _) | Expr::Pow(_, _)) && log.explains()
+	{
+		let (written, reduced) = (expr.write(names), poly.short_with(names.first().map_or("x", |name| name.as_str())));
+		if written != reduced { log.record(Verbosity::Explain, format!("{} = {}", written, reduced)) }
+	}
+	Ok(poly)
+}
+
+// Expand an expression of degree 1 in several unknowns, the result is stored as a polynomial
+// where the coefficient of degree i + 1 is the one of the unknown i and the coefficient of degree 0 the constant
+// ex: 2x - y + 3 with the unknowns [x, y] => Polynomial [3, 2, -1]
+pub fn to_linear<T: Scalar>((expr, span): &SpannedExpr<T>, names: &[String]) -> Result<Polynomial<T>, ComputorError>
+{
+	let not_linear = || ComputorError::syntax(SyntaxKind::NotLinear, Some(*span));
+	let form = match expr
+	{
+		Expr::Num(n) => Polynomial::term(*n, 0),
+		Expr::Var(i) => Polynomial::term(T::one(), *i as u32 + 1),
+		Expr::Neg(e) => to_linear(e, names)?.scale(-T::one()),
+		Expr::Add(a, b) => to_linear(a, names)? + to_linear(b, names)?,
+		// One of the factors must be a number
+		Expr::Mul(a, b) =>
+		{
+			let (a, b) = (to_linear(a, names)?, to_linear(b, names)?);
+			match (a.is_constant(), b.is_constant())
+			{
+				(true, _) => b.scale(a.coefficient(0)),
+				(_, true) => a.scale(b.coefficient(0)),
+				_ => return Err(not_linear())
+			}
+		}
+		Expr::Div(a, b) =>
+		{
+			let divisor = to_linear(b, names)?;
+			if !divisor.is_constant() { return Err(not_linear()) }
+			to_linear(a, names)?.divide(divisor.coefficient(0)).map_err(|e| division_error(e, &a.0, names, *span))?
+		}
+		Expr::Pow(a, n) =>
+		{
+			let base = to_linear(a, names)?;
+			match (base.is_constant(), n)
+			{
+				(true, _) => Polynomial::term(pow(base.coefficient(0), *n), 0),
+				(false, 0) => Polynomial::term(T::one(), 0),
+				(false, 1) => base,
+				_ => return Err(not_linear())
+			}
+		}
+	};
+	Ok(form)
+}
@@ -0,0 +1,89 @@
+use crate::{format_number, multiplier, Polynomial, Rational, Scalar};
+use crate::radical;
+use crate::solver::{scale, stable_roots};
+
+// The vertex (h, k) of the parabola Y = aX² + bX + c, X = h is its axis of symmetry
+// ex: 2x² + 4x - 6 => (-1, -8)
+pub fn vertex<T: Scalar>(poly: &Polynomial<T>) -> Option<(T, T)>
+{
+	if poly.degree() != 2 { return None }
+	let (a, b, c) = (poly.coefficient(2), poly.coefficient(1), poly.coefficient(0));
+	let two = T::one() + T::one();
+	let h = -b / (two * a);
+	Some((h, c + b * h / two))
+}
+
+// X - root, with the sign of the root. ex: (x + 3) | (x - 1/2) | x
+pub(crate) fn factor(name: &str, root: &str) -> String
+{
+	match (root, root.strip_prefix('-'))
+	{
+		("0", _) => name.to_string(),
+		(_, Some(root)) => format!("({} + {})", name, root),
+		_ => format!("({} - {})", name, root)
+	}
+}
+
+// The coefficient written in front of a product. ex: 2 => 2 | 1 => "" | -1 => - | 1/3 => (1/3)
+fn leading(a: String) -> String
+{
+	match a.as_str()
+	{
+		"1" => String::new(),
+		"-1" => "-".to_string(),
+		_ => multiplier(a)
+	}
+}
+
+// a(X - h)² + k
+// ex: 2x² + 4x - 6 => 2(x + 1)² - 8
+pub fn vertex_form<T: Scalar>(poly: &Polynomial<T>, name: &str) -> Option<String>
+{
+	let (h, k) = vertex(poly)?;
+	let square = format!("{}{}²", leading(poly.coefficient(2).format()), factor(name, &h.format()));
+	let k = k.format();
+	let form = match (k.as_str(), k.strip_prefix('-'))
+	{
+		("0", _) => square,
+		(_, Some(k)) => format!("{} - {}", square, k),
+		_ => format!("{} + {}", square, k)
+	};
+	Some(form)
+}
+
+// A fraction as a number of the polynomial. ex: -1/3 => -1/3 in exact mode, -0.33 otherwise
+fn from_rational<T: Scalar>(n: Rational) -> Option<T>
+{
+	let num = T::from_literal(&n.numerator().abs().to_string())?;
+	let den = T::from_literal(&n.denominator().to_string())?;
+	Some(if n.numerator() < 0 { -(num / den) } else { num / den })
+}
+
+// a(X - S1)(X - S2) when the roots are real, they are written as fractions when they are rational in exact mode
+// ex: 2x² + 4x - 6 => 2(x + 3)(x - 1)
+pub fn factored_form<T: Scalar>(poly: &Polynomial<T>, name: &str) -> Option<String>
+{
+	if poly.degree() != 2 { return None }
+	let a = poly.coefficient(2);
+	let (fa, fb, fc) = (a.to_f64(), poly.coefficient(1).to_f64(), poly.coefficient(0).to_f64());
+	// The scaled coefficients have the same roots and b² - 4ac can't overflow
+	let (sa, sb, sc, _) = scale(fa, fb, fc);
+	let delta = sb * sb - 4.0 * sa * sc;
+	if delta < 0.0 || delta.is_nan() { return None }
+	let exact = radical::quadratic_surd(fa, fb, fc).and_then(|surd| surd.rational_roots());
+	let (s1, s2) = match exact
+	{
+		Some((s1, s2)) => (from_rational::<T>(s1)?.format(), from_rational::<T>(s2)?.format()),
+		None =>
+		{
+			let (s1, s2) = stable_roots(sa, sb, sc, delta.sqrt());
+			(format_number(s1), format_number(s2))
+		}
+	};
+	let product = match s1 == s2
+	{
+		true => format!("{}²", factor(name, &s1)),
+		false => format!("{}{}", factor(name, &s1), factor(name, &s2))
+	};
+	Some(format!("{}{}", leading(a.format()), product))
+}
@@ -0,0 +1,434 @@
+use std::fmt;
+
+use crate::{format_number, latex_number, Latex};
+
+// One end of an interval, an infinite bound is -∞ on the left and ∞ on the right
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bound
+{
+	Open(f64),
+	Closed(f64),
+	Infinite
+}
+
+// The real numbers between two bounds
+// ex:	Interval { low: Bound::Infinite, high: Bound::Closed(2.0) } => (-∞, 2]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval
+{
+	pub low: Bound,
+	pub high: Bound
+}
+
+impl Bound
+{
+	// Sort keys of the bounds, an open low bound starts just after its value
+	// and an open high bound ends just before it
+	fn low_key(&self) -> (f64, u8)
+	{
+		match self
+		{
+			Bound::Closed(n) => (*n, 0),
+			Bound::Open(n) => (*n, 1),
+			Bound::Infinite => (f64::NEG_INFINITY, 0)
+		}
+	}
+
+	fn high_key(&self) -> (f64, u8)
+	{
+		match self
+		{
+			Bound::Open(n) => (*n, 0),
+			Bound::Closed(n) => (*n, 1),
+			Bound::Infinite => (f64::INFINITY, 1)
+		}
+	}
+
+	// The bound on the other side of the same value. ex: 2) => [2
+	fn flip(&self) -> Bound
+	{
+		match self
+		{
+			Bound::Open(n) => Bound::Closed(*n),
+			Bound::Closed(n) => Bound::Open(*n),
+			Bound::Infinite => Bound::Infinite
+		}
+	}
+}
+
+impl Interval
+{
+	pub fn new(low: Bound, high: Bound) -> Self
+	{
+		Interval { low, high }
+	}
+
+	// The interval containing a single value. ex: [2, 2]
+	pub fn point(n: f64) -> Self
+	{
+		Interval { low: Bound::Closed(n), high: Bound::Closed(n) }
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		match (self.low, self.high)
+		{
+			(Bound::Closed(low), Bound::Closed(high)) => low > high,
+			(Bound::Open(low), Bound::Open(high))
+			| (Bound::Open(low), Bound::Closed(high))
+			| (Bound::Closed(low), Bound::Open(high)) => low >= high,
+			_ => false
+		}
+	}
+
+	pub fn is_point(&self) -> bool
+	{
+		matches!((self.low, self.high), (Bound::Closed(low), Bound::Closed(high)) if low == high)
+	}
+
+	pub fn contains(&self, x: f64) -> bool
+	{
+		let above = match self.low
+		{
+			Bound::Open(n) => x > n,
+			Bound::Closed(n) => x >= n,
+			Bound::Infinite => true
+		};
+		let below = match self.high
+		{
+			Bound::Open(n) => x < n,
+			Bound::Closed(n) => x <= n,
+			Bound::Infinite => true
+		};
+		above && below
+	}
+
+	// The highest low bound and the lowest high bound, the result may be empty
+	pub fn intersection(&self, other: &Interval) -> Interval
+	{
+		let low = if self.low.low_key() >= other.low.low_key() { self.low } else { other.low };
+		let high = if self.high.high_key() <= other.high.high_key() { self.high } else { other.high };
+		Interval { low, high }
+	}
+
+	// True when other starts before the end of self or right at it,
+	// self must not start after other
+	fn touches(&self, other: &Interval) -> bool
+	{
+		match (self.high, other.low)
+		{
+			(Bound::Infinite, _) | (_, Bound::Infinite) => true,
+			(Bound::Open(high), Bound::Open(low)) => low < high,
+			(Bound::Open(high), Bound::Closed(low))
+			| (Bound::Closed(high), Bound::Open(low))
+			| (Bound::Closed(high), Bound::Closed(low)) => low <= high
+		}
+	}
+}
+
+// The real values of X verifying an equation or an inequality
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolutionSet
+{
+	Empty,
+	All,
+
+	// A finite set of values sorted by increasing order. ex: {-2, 2}
+	Values(Vec<f64>),
+
+	// A union of intervals sorted by increasing order. ex: (-∞, -2) ∪ (2, ∞)
+	Intervals(Vec<Interval>)
+}
+
+impl SolutionSet
+{
+	// The normalized union of a list of intervals:
+	// the empty intervals are removed, the others are sorted and the overlapping ones are merged,
+	// then the set is reduced to the simplest variant
+	// ex: [(1, 3), [2, 5], [-1, -1]] => {-1} ∪ (1, 5]
+	pub fn from_intervals(intervals: Vec<Interval>) -> SolutionSet
+	{
+		let mut intervals: Vec<Interval> = intervals.into_iter().filter(|i| !i.is_empty()).collect();
+		intervals.sort_by(|i1, i2| i1.low.low_key().partial_cmp(&i2.low.low_key()).unwrap());
+		let mut merged: Vec<Interval> = vec![];
+		for interval in intervals
+		{
+			match merged.last_mut()
+			{
+				Some(last) if last.touches(&interval) =>
+				{
+					if interval.high.high_key() > last.high.high_key() { last.high = interval.high }
+				}
+				_ => merged.push(interval)
+			}
+		}
+		match merged.as_slice()
+		{
+			[] => SolutionSet::Empty,
+			[Interval { low: Bound::Infinite, high: Bound::Infinite }] => SolutionSet::All,
+			_ if merged.iter().all(|i| i.is_point()) => SolutionSet::Values(merged.iter().map(|i| i.low.low_key().0).collect()),
+			_ => SolutionSet::Intervals(merged)
+		}
+	}
+
+	// The set as a list of intervals, a value is a single point interval
+	pub fn intervals(&self) -> Vec<Interval>
+	{
+		match self
+		{
+			SolutionSet::Empty => vec![],
+			SolutionSet::All => vec![Interval::new(Bound::Infinite, Bound::Infinite)],
+			SolutionSet::Values(values) => values.iter().map(|n| Interval::point(*n)).collect(),
+			SolutionSet::Intervals(intervals) => intervals.clone()
+		}
+	}
+
+	pub fn normalize(&self) -> SolutionSet
+	{
+		SolutionSet::from_intervals(self.intervals())
+	}
+
+	pub fn contains(&self, x: f64) -> bool
+	{
+		self.intervals().iter().any(|i| i.contains(x))
+	}
+
+	pub fn union(&self, other: &SolutionSet) -> SolutionSet
+	{
+		SolutionSet::from_intervals(self.intervals().into_iter().chain(other.intervals()).collect())
+	}
+
+	pub fn intersection(&self, other: &SolutionSet) -> SolutionSet
+	{
+		let mut intervals: Vec<Interval> = vec![];
+		for i1 in self.intervals()
+		{
+			for i2 in other.intervals()
+			{
+				intervals.push(i1.intersection(&i2));
+			}
+		}
+		SolutionSet::from_intervals(intervals)
+	}
+
+	// The real numbers that are not in the set, found in the gaps between its intervals
+	// ex: (-∞, -2] ∪ (2, 3) => (-2, 2] ∪ [3, ∞)
+	pub fn complement(&self) -> SolutionSet
+	{
+		let mut gaps: Vec<Interval> = vec![];
+		let mut low = Bound::Infinite;
+		for interval in self.normalize().intervals()
+		{
+			if interval.low != Bound::Infinite
+			{
+				gaps.push(Interval::new(low, interval.low.flip()));
+			}
+			low = interval.high.flip();
+			if interval.high == Bound::Infinite
+			{
+				return SolutionSet::from_intervals(gaps);
+			}
+		}
+		gaps.push(Interval::new(low, Bound::Infinite));
+		SolutionSet::from_intervals(gaps)
+	}
+}
+
+impl fmt::Display for Interval
+{
+	// ex: (-∞, 2] | (-1, 3) | {4}
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		if self.is_point()
+		{
+			return write!(f, "{{{}}}", format_number(self.low.low_key().0));
+		}
+		let low = match self.low
+		{
+			Bound::Open(n) => format!("({}", format_number(n)),
+			Bound::Closed(n) => format!("[{}", format_number(n)),
+			Bound::Infinite => "(-∞".to_string()
+		};
+		let high = match self.high
+		{
+			Bound::Open(n) => format!("{})", format_number(n)),
+			Bound::Closed(n) => format!("{}]", format_number(n)),
+			Bound::Infinite => "∞)".to_string()
+		};
+		write!(f, "{}, {}", low, high)
+	}
+}
+
+impl fmt::Display for SolutionSet
+{
+	// ex: ∅ | ℝ | 3 | {-2, 2} | (-∞, -2) ∪ (2, ∞)
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		let to_display = match self
+		{
+			SolutionSet::Empty => "∅".to_string(),
+			SolutionSet::All => "ℝ".to_string(),
+			SolutionSet::Values(values) if values.len() == 1 => format_number(values[0]),
+			SolutionSet::Values(values) =>
+			{
+				let values: Vec<String> = values.iter().map(|n| format_number(*n)).collect();
+				format!("{{{}}}", values.join(", "))
+			}
+			SolutionSet::Intervals(intervals) =>
+			{
+				let intervals: Vec<String> = intervals.iter().map(|i| i.to_string()).collect();
+				intervals.join(" ∪ ")
+			}
+		};
+		write!(f, "{}", to_display)
+	}
+}
+
+impl Latex for Interval
+{
+	// ex: (-\infty, 2] | \{4\}
+	fn latex(&self) -> String
+	{
+		let number = |n: f64| latex_number(&format_number(n));
+		if self.is_point()
+		{
+			return format!("\\{{{}\\}}", number(self.low.low_key().0));
+		}
+		let low = match self.low
+		{
+			Bound::Open(n) => format!("({}", number(n)),
+			Bound::Closed(n) => format!("[{}", number(n)),
+			Bound::Infinite => "(-\\infty".to_string()
+		};
+		let high = match self.high
+		{
+			Bound::Open(n) => format!("{})", number(n)),
+			Bound::Closed(n) => format!("{}]", number(n)),
+			Bound::Infinite => "\\infty)".to_string()
+		};
+		format!("{}, {}", low, high)
+	}
+}
+
+impl Latex for SolutionSet
+{
+	// ex: \emptyset | \mathbb{R} | \{-2, 2\} | (-\infty, 2] \cup [3, \infty)
+	fn latex(&self) -> String
+	{
+		match self
+		{
+			SolutionSet::Empty => "\\emptyset".to_string(),
+			SolutionSet::All => "\\mathbb{R}".to_string(),
+			SolutionSet::Values(values) =>
+			{
+				let values: Vec<String> = values.iter().map(|n| latex_number(&format_number(*n))).collect();
+				format!("\\{{{}\\}}", values.join(", "))
+			}
+			SolutionSet::Intervals(intervals) =>
+			{
+				let intervals: Vec<String> = intervals.iter().map(|i| i.latex()).collect();
+				intervals.join(" \\cup ")
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use Bound::{Closed, Infinite, Open};
+
+	fn set(intervals: &[(Bound, Bound)]) -> SolutionSet
+	{
+		SolutionSet::from_intervals(intervals.iter().map(|(low, high)| Interval::new(*low, *high)).collect())
+	}
+
+	#[test]
+	fn empty_intervals()
+	{
+		assert!(Interval::new(Open(1.0), Open(1.0)).is_empty());
+		assert!(Interval::new(Closed(1.0), Open(1.0)).is_empty());
+		assert!(!Interval::point(1.0).is_empty());
+		assert_eq!(set(&[(Open(2.0), Closed(1.0))]), SolutionSet::Empty);
+	}
+
+	#[test]
+	fn contains_bounds()
+	{
+		let interval = Interval::new(Open(-1.0), Closed(2.0));
+		assert!(!interval.contains(-1.0));
+		assert!(interval.contains(0.0));
+		assert!(interval.contains(2.0));
+		assert!(!interval.contains(2.5));
+		assert!(Interval::new(Infinite, Open(0.0)).contains(-1e300));
+		assert!(set(&[(Infinite, Open(-2.0)), (Closed(2.0), Infinite)]).contains(2.0));
+		assert!(!SolutionSet::Empty.contains(0.0));
+	}
+
+	#[test]
+	fn normalize_merges_overlapping_intervals()
+	{
+		assert_eq!(set(&[(Open(2.0), Closed(5.0)), (Open(1.0), Open(3.0)), (Closed(-1.0), Closed(-1.0))]),
+			SolutionSet::Intervals(vec![Interval::point(-1.0), Interval::new(Open(1.0), Closed(5.0))]));
+		assert_eq!(set(&[(Open(1.0), Open(4.0)), (Closed(2.0), Closed(3.0))]), set(&[(Open(1.0), Open(4.0))]));
+		assert_eq!(set(&[(Infinite, Closed(0.0)), (Closed(-1.0), Infinite)]), SolutionSet::All);
+	}
+
+	#[test]
+	fn touching_bounds()
+	{
+		// The common bound is in one of the intervals
+		assert_eq!(set(&[(Open(1.0), Closed(2.0)), (Open(2.0), Open(3.0))]), set(&[(Open(1.0), Open(3.0))]));
+		assert_eq!(set(&[(Open(1.0), Open(2.0)), (Closed(2.0), Open(3.0))]), set(&[(Open(1.0), Open(3.0))]));
+		// The common bound is in none of them
+		assert_eq!(set(&[(Open(1.0), Open(2.0)), (Open(2.0), Open(3.0))]),
+			SolutionSet::Intervals(vec![Interval::new(Open(1.0), Open(2.0)), Interval::new(Open(2.0), Open(3.0))]));
+	}
+
+	#[test]
+	fn points()
+	{
+		assert_eq!(set(&[(Closed(2.0), Closed(2.0)), (Closed(-2.0), Closed(-2.0))]), SolutionSet::Values(vec![-2.0, 2.0]));
+		assert_eq!(SolutionSet::Values(vec![1.0, 1.0]).normalize(), SolutionSet::Values(vec![1.0]));
+		// A point at the open bound of an interval closes it
+		assert_eq!(SolutionSet::Values(vec![1.0]).union(&set(&[(Open(1.0), Infinite)])), set(&[(Closed(1.0), Infinite)]));
+		assert_eq!(SolutionSet::Values(vec![3.0]).union(&set(&[(Open(1.0), Open(3.0))])), set(&[(Open(1.0), Closed(3.0))]));
+		// A point inside an interval disappears
+		assert_eq!(SolutionSet::Values(vec![2.0]).union(&set(&[(Open(1.0), Open(3.0))])), set(&[(Open(1.0), Open(3.0))]));
+	}
+
+	#[test]
+	fn union_and_intersection()
+	{
+		let a = set(&[(Infinite, Open(0.0)), (Closed(2.0), Closed(4.0))]);
+		let b = set(&[(Open(-1.0), Open(3.0))]);
+		assert_eq!(a.union(&b), set(&[(Infinite, Closed(4.0))]));
+		assert_eq!(a.intersection(&b), set(&[(Open(-1.0), Open(0.0)), (Closed(2.0), Open(3.0))]));
+		assert_eq!(a.intersection(&SolutionSet::Empty), SolutionSet::Empty);
+		assert_eq!(a.union(&SolutionSet::All), SolutionSet::All);
+		assert_eq!(set(&[(Open(0.0), Closed(1.0))]).intersection(&set(&[(Closed(1.0), Open(2.0))])), SolutionSet::Values(vec![1.0]));
+	}
+
+	#[test]
+	fn complement()
+	{
+		assert_eq!(SolutionSet::Empty.complement(), SolutionSet::All);
+		assert_eq!(SolutionSet::All.complement(), SolutionSet::Empty);
+		assert_eq!(set(&[(Infinite, Closed(-2.0)), (Open(2.0), Open(3.0))]).complement(),
+			set(&[(Open(-2.0), Closed(2.0)), (Closed(3.0), Infinite)]));
+		// The bounds at ±∞ have no gap before or after them
+		assert_eq!(set(&[(Infinite, Open(0.0))]).complement(), set(&[(Closed(0.0), Infinite)]));
+		assert_eq!(set(&[(Closed(0.0), Infinite)]).complement(), set(&[(Infinite, Open(0.0))]));
+		assert_eq!(SolutionSet::Values(vec![-1.0, 1.0]).complement(),
+			set(&[(Infinite, Open(-1.0)), (Open(-1.0), Open(1.0)), (Open(1.0), Infinite)]));
+	}
+
+	#[test]
+	fn display()
+	{
+		assert_eq!(set(&[(Infinite, Open(-2.0)), (Closed(2.0), Infinite)]).to_string(), "(-∞, -2) ∪ [2, ∞)");
+		assert_eq!(SolutionSet::Values(vec![-2.0, 2.0]).to_string(), "{-2, 2}");
+		assert_eq!(SolutionSet::Empty.to_string(), "∅");
+	}
+}
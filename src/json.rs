@@ -0,0 +1,158 @@
+use crate::{Bound, Comparison, ComputorError, Interval, Polynomial, Scalar, Solution};
+use crate::{Parametric, SystemSet, SystemSolution};
+use crate::numeric::Residual;
+
+// A JSON string with the quotes, backslashes and control characters escaped
+pub fn string(s: &str) -> String
+{
+	let mut escaped = String::from("\"");
+	for c in s.chars()
+	{
+		match c
+		{
+			'"' => escaped.push_str("\\\""),
+			'\\' => escaped.push_str("\\\\"),
+			'\n' => escaped.push_str("\\n"),
+			'\t' => escaped.push_str("\\t"),
+			_ if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+			_ => escaped.push(c)
+		}
+	}
+	escaped.push('"');
+	escaped
+}
+
+// JSON has no infinite number, they are written as null
+pub fn number(n: f64) -> String
+{
+	match n.is_finite()
+	{
+		// Avoid writing -0
+		true => format!("{}", if n == 0.0 { 0.0 } else { n }),
+		false => "null".to_string()
+	}
+}
+
+// An infinite bound is null. ex: { "value": 2, "closed": true }
+fn bound(bound: &Bound) -> String
+{
+	match bound
+	{
+		Bound::Open(n) => format!("{{\"value\": {}, \"closed\": false}}", number(*n)),
+		Bound::Closed(n) => format!("{{\"value\": {}, \"closed\": true}}", number(*n)),
+		Bound::Infinite => "null".to_string()
+	}
+}
+
+pub fn interval(interval: &Interval) -> String
+{
+	format!("{{\"low\": {}, \"high\": {}}}", bound(&interval.low), bound(&interval.high))
+}
+
+fn list(items: Vec<String>) -> String
+{
+	format!("[{}]", items.join(", "))
+}
+
+// The reduced polynomial and its solution as one object
+// The coefficients are sorted by increasing degree and the solution set is a list of intervals
+// The residuals are only written when the roots are verified
+pub fn report<T: Scalar>(expression: &str, poly: &Polynomial<T>, cmp: &Comparison, variable: &str, solution: Option<&Solution>,
+	residuals: Option<&[Residual]>) -> String
+{
+	let coefficients: Vec<String> = poly.coefficients().iter().map(|n| number(n.to_f64())).collect();
+	let operator = match cmp
+	{
+		Comparison::No => "null".to_string(),
+		_ => string(&cmp.to_string())
+	};
+	let mut fields = vec![
+		format!("\"expression\": {}", string(expression)),
+		format!("\"variable\": {}", string(variable)),
+		format!("\"coefficients\": {}", list(coefficients)),
+		format!("\"degree\": {}", poly.degree()),
+		format!("\"operator\": {}", operator)
+	];
+	if let Some(solution) = solution
+	{
+		let discriminant = solution.discriminant.map_or("null".to_string(), number);
+		let roots: Vec<String> = solution.roots.iter()
+			.map(|s| format!("{{\"re\": {}, \"im\": {}}}", number(s.re), number(s.im)))
+			.collect();
+		let intervals = match &solution.set
+		{
+			Some(set) => list(set.intervals().iter().map(interval).collect()),
+			None => "null".to_string()
+		};
+		fields.push(format!("\"discriminant\": {}", discriminant));
+		// Δ = discriminant × discriminant_factor², the factor is 1 unless Δ overflows or X is substituted
+		let factor = solution.discriminant.map_or("null".to_string(), |_| number(solution.discriminant_factor));
+		fields.push(format!("\"discriminant_factor\": {}", factor));
+		fields.push(format!("\"roots\": {}", list(roots)));
+		// The forward error estimate of each root, only for the equations of degree 2
+		let errors = match solution.errors.is_empty()
+		{
+			true => "null".to_string(),
+			false => list(solution.errors.iter().map(|e| number(*e)).collect())
+		};
+		fields.push(format!("\"errors\": {}", errors));
+		fields.push(format!("\"approximate\": {}", solution.approximate));
+		fields.push(format!("\"intervals\": {}", intervals));
+		fields.push(format!("\"solution\": {}", string(&solution.conclusion())));
+	}
+	if let Some(residuals) = residuals
+	{
+		let residuals: Vec<String> = residuals.iter()
+			.map(|r| format!("{{\"re\": {}, \"im\": {}, \"residual\": {}, \"relative\": {}, \"doubtful\": {}}}",
+				number(r.root.re), number(r.root.im), number(r.residual), number(r.relative), r.doubtful))
+			.collect();
+		fields.push(format!("\"residuals\": {}", list(residuals)));
+	}
+	fields.push("\"error\": null".to_string());
+	format!("{{{}}}", fields.join(", "))
+}
+
+// A linear system and its solution as one object, the values are given by unknown:
+// a number when the solution is unique, null for a free unknown,
+// or the constant and the factor of each free unknown. ex: {"constant": 1, "parameters": {"z": -2}}
+pub fn system_report<T: Scalar>(expression: &str, solution: &SystemSolution<T>) -> String
+{
+	let unknowns: Vec<String> = solution.unknowns.iter().map(|name| string(name)).collect();
+	let field = |name: &String, value: String| format!("{}: {}", string(name), value);
+	let (kind, values) = match &solution.set
+	{
+		SystemSet::Empty => ("empty", "null".to_string()),
+		SystemSet::Unique(values) =>
+		{
+			let values: Vec<String> = solution.unknowns.iter().zip(values).map(|(name, n)| field(name, number(n.to_f64()))).collect();
+			("unique", format!("{{{}}}", values.join(", ")))
+		}
+		SystemSet::Infinite(values) =>
+		{
+			let values: Vec<String> = solution.unknowns.iter().zip(values).map(|(name, value)| match value
+			{
+				Parametric::Free => field(name, "null".to_string()),
+				Parametric::Depends(c, free) =>
+				{
+					let parameters: Vec<String> = free.iter().map(|(j, n)| field(&solution.unknowns[*j], number(n.to_f64()))).collect();
+					field(name, format!("{{\"constant\": {}, \"parameters\": {{{}}}}}", number(c.to_f64()), parameters.join(", ")))
+				}
+			}).collect();
+			("infinite", format!("{{{}}}", values.join(", ")))
+		}
+	};
+	format!("{{\"expression\": {}, \"unknowns\": {}, \"kind\": {}, \"values\": {}, \"solution\": {}, \"error\": null}}",
+		string(expression), list(unknowns), string(kind), values, string(&solution.conclusion()))
+}
+
+// ex: {"expression": "2x @ 3", "error": {"kind": "lexical", "message": "...", "position": {"start": 3, "end": 4}}}
+pub fn error_report(expression: &str, error: &ComputorError) -> String
+{
+	let position = match error.span()
+	{
+		Some(span) => format!("{{\"start\": {}, \"end\": {}}}", span.start, span.end),
+		None => "null".to_string()
+	};
+	format!("{{\"expression\": {}, \"error\": {{\"kind\": {}, \"message\": {}, \"position\": {}}}}}",
+		string(expression), string(error.kind()), string(&error.to_string()), position)
+}
@@ -1,7 +1,32 @@
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+pub mod complex;
+pub mod error;
+pub mod expr;
+pub mod forms;
+pub mod interval;
+pub mod json;
+pub mod numeric;
 pub mod parser;
+pub mod polynomial;
+pub mod radical;
+pub mod rational;
+pub mod scalar;
 pub mod solver;
+pub mod steps;
+pub mod system;
+
+pub use complex::Complex;
+pub use error::{ComputorError, SyntaxKind, Span};
+pub use expr::{Expr, SpannedExpr};
+pub use interval::{Bound, Interval, SolutionSet};
+pub use polynomial::Polynomial;
+pub use rational::Rational;
+pub use scalar::Scalar;
+pub use solver::{Method, Solution};
+pub use steps::{StepLog, Verbosity};
+pub use system::{LinearEquation, Parametric, SystemSet, SystemSolution};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Comparison
@@ -31,6 +56,26 @@ impl fmt::Display for Comparison
     }
 }
 
+// Write a value as LaTeX math, for the course notes
+// ex: 3x² - 2x + 1 = 0 => 3x^{2} - 2x + 1 = 0
+pub trait Latex
+{
+	fn latex(&self) -> String;
+}
+
+impl Latex for Comparison
+{
+	fn latex(&self) -> String
+	{
+		match self
+		{
+			Comparison::GreaterEq => "\\geq".to_string(),
+			Comparison::LowerEq => "\\leq".to_string(),
+			_ => self.to_string()
+		}
+	}
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Operator
 {
@@ -55,59 +100,85 @@ impl fmt::Display for Operator
     }
 }
 
+impl Latex for Operator
+{
+	fn latex(&self) -> String
+	{
+		match self
+		{
+			Operator::Mult => "\\times".to_string(),
+			_ => self.to_string()
+		}
+	}
+}
+
 #[derive(Debug, Clone, PartialEq)]
-pub enum Token
+pub enum Token<T = f64>
 {
-	// f64 is the coefficient and u8 the degree
+	// T is the coefficient (f64, or Rational in exact mode) and u32 the degree
 	// ex:	Var(2.0, 2) => 2.0 * x²
 	//      Var(-65.7, 1) => -65.7 * x
 	// 		Var(3.2, 0) => 3.2 * x ^ 0
 	//					 = 3.2 * 1
 	//					 = 3.2
-	Var((f64, u8)),
+	Var((T, u32)),
 
 	// '+' | '-' | '/' | '*'
 	Operator(Operator),
 
 	// '>' | '<' | '>=' | '<=' | '='
 	Cmp(Comparison),
+
+	// '(' | ')'
+	Open,
+	Close,
+
+	// The exponent of a parenthesized sub-expression
+	// ex:	(x + 1)^2 => Open, Var(1, 1), Operator(Add), Var(1, 0), Close, Power(2)
+	Power(u32)
 }
 
-impl Token
+// A token with the characters of the expression it was read from
+// ex: "2 + 4.5x^2" => (Var(4.5, 2), Span { start: 4, end: 10 })
+pub type SpannedToken<T = f64> = (Token<T>, Span);
+
+// The number of decimals displayed by format_number
+static PRECISION: AtomicUsize = AtomicUsize::new(2);
+
+pub fn set_precision(precision: usize)
 {
-	pub fn same_type_as(&self, other: &Self) -> bool
-	{
-		match self
-		{
-			Token::Var((_, 0)) => if let Token::Var((_, 0)) = other { true } else { false }
-			Token::Var(_) => if let Token::Var(_) = other { true } else { false }
-			Token::Operator(_) => if let Token::Operator(_) = other { true } else { false }
-			Token::Cmp(_) => if let Token::Cmp(_) = other { true } else { false }
-		}
-	}
+	PRECISION.store(precision, Ordering::Relaxed);
+}
 
-	pub fn is_variable(&self) -> bool
-	{
-		match self
-		{
-			Token::Var(_) => true,
-			_ => false
-		}
-	}
+pub fn precision() -> usize
+{
+	PRECISION.load(Ordering::Relaxed)
 }
 
 pub fn format_number(n: f64) -> String
 {
+	// Avoid displaying -0
+	let n = if n == 0.0 { 0.0 } else { n };
+	let precision = precision();
+
 	// Check if number is infinite or not
 	match n.is_finite()
 	{
 		// match n if (too many digits, 	 too many 0's,              is not an integer)
-		true => match (n >= 1e6 || n < -1e6, abs(n) < 1e-2 && n != 0.0, n.floor() != n)
+		true => match (!(-1e6..1e6).contains(&n), abs(n) < 10_f64.powi(-(precision as i32)) && n != 0.0, n.floor() != n)
 		{
-			// If too many digits to display (more than 6)=> print with scientific notation with a precision of 2
-			(true, ..) | (_, true, _)=> format!("{:.2e}", n),
-			// If regular float
-			(false, false, true) => format!("{:.2}", n),
+			// If too many digits to display (more than 6)=> print with scientific notation
+			(true, ..) | (_, true, _)=> format!("{:.*e}", precision, n),
+			// If regular float, a rounding error close to an integer is displayed as an integer
+			(false, false, true) =>
+			{
+				let number = format!("{:.*}", precision, n);
+				match number.find('.')
+				{
+					Some(dot) if number[dot + 1..].chars().all(|c| c == '0') => number[..dot].to_string(),
+					_ => number
+				}
+			}
 			// If integer
             (false, false, false) => format!("{}", n),
 		}
@@ -115,61 +186,128 @@ pub fn format_number(n: f64) -> String
 		{
 			match n.is_sign_positive()
 			{
-				true => format!("∞"),
-				false => format!("-∞")
+				true => "∞".to_string(),
+				false => "-∞".to_string()
 			}
 		}
 	}
 }
 
-impl fmt::Display for Token
+impl<T: Scalar> Token<T>
 {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
-    {
-        let to_display = match self
+	// The token written with name as the unknown. ex: Var(3, 2) with "t" => 3t²
+	pub fn display_with(&self, name: &str) -> String
+	{
+		match self
 		{
 			Token::Var((n, d)) =>
 			{
 				match d
 				{
-					2 if *n == 1.0 => format!("x²"),
-                    2 => format!("{}x²", format_number(*n)),
-					1 if *n == 1.0 => format!("x"),
-                    1 => format!("{}x", format_number(*n)),
-					0 | _ => format!("{}", format_number(*n))
+					0 => n.format(),
+					1 if *n == T::one() => name.to_string(),
+					1 => format!("{}{}", multiplier(n.format()), name),
+					_ if *n == T::one() => format!("{}{}", name, superscript(*d)),
+					_ => format!("{}{}{}", multiplier(n.format()), name, superscript(*d))
 				}
 			}
 			Token::Operator(op) => format!("{}", op),
-			Token::Cmp(c) => format!("{}", c)
-		};
-        write!(f, "{}", to_display)
+			Token::Cmp(c) => format!("{}", c),
+			Token::Open => "(".to_string(),
+			Token::Close => ")".to_string(),
+			Token::Power(n) => superscript(*n)
+		}
+	}
+
+	// ex: Var(-7/3, 2) with "t" => -\frac{7}{3}t^{2}
+	pub fn latex_with(&self, name: &str) -> String
+	{
+		match self
+		{
+			Token::Var((n, d)) =>
+			{
+				let n = if *n == T::one() && *d > 0 { String::new() } else { latex_number(&n.format()) };
+				match d
+				{
+					0 => n,
+					1 => format!("{}{}", n, name),
+					_ => format!("{}{}^{{{}}}", n, name, d)
+				}
+			}
+			Token::Operator(op) => op.latex(),
+			Token::Cmp(c) => c.latex(),
+			Token::Open => "\\left(".to_string(),
+			Token::Close => "\\right)".to_string(),
+			Token::Power(n) => format!("^{{{}}}", n)
+		}
+	}
+}
+
+impl<T: Scalar> fmt::Display for Token<T>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}", self.display_with("x"))
     }
 }
 
+impl<T: Scalar> Latex for Token<T>
+{
+	// ex: Var(-7/3, 2) => -\frac{7}{3}x^{2}
+	fn latex(&self) -> String
+	{
+		self.latex_with("x")
+	}
+}
+
+pub fn latex_number(n: &str) -> String
+{
+	let (sign, n) = match n.strip_prefix('-')
+	{
+		Some(n) => ("-", n),
+		None => ("", n)
+	};
+	let n = match (n.split_once('/'), n.split_once('e'))
+	{
+		(Some((num, den)), _) => format!("\\frac{{{}}}{{{}}}", num, den),
+		(_, Some((mantissa, exponent))) => format!("{} \\times 10^{{{}}}", mantissa, exponent),
+		_ => n.replace('∞', "\\infty")
+	};
+	format!("{}{}", sign, n)
+}
 
-pub fn pow(x: f64, n: u8) -> f64
+// A number written in front of what it multiplies, a fraction is parenthesized so that it isn't read as a division of it
+// ex: 1/3 => (1/3) | -1/3 => -(1/3) | 2 => 2
+pub fn multiplier(n: String) -> String
 {
-	let mut number: f64 = 1.0;
-	for _ in 0..n
+	match (n.contains('/'), n.strip_prefix('-'))
 	{
-		number *= x;
+		(true, Some(n)) => format!("-({})", n),
+		(true, None) => format!("({})", n),
+		_ => n
 	}
-	number
 }
 
-pub fn do_op(a: f64, b: f64, op: &Operator) -> Result<f64, String>
+// Write a degree with superscript digits. ex: 12 => ¹²
+pub fn superscript(n: u32) -> String
+{
+	let digits = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+	n.to_string().chars().map(|c| digits[c.to_digit(10).unwrap() as usize]).collect()
+}
+
+// Exponentiation by squaring
+pub fn pow<T: Scalar>(x: T, n: u32) -> T
 {
-	match op
+	let mut number = T::one();
+	let mut base = x;
+	let mut n = n;
+	while n > 0
 	{
-		Operator::Add => Ok(a + b),
-		Operator::Sub => Ok(a - b),
-		Operator::Mult => Ok(a * b),
-		Operator::Div =>
-		{
-			if b != 0.0 { Ok(a / b) }
-			else { Err(format!("Syntax error: attempted to divide {} by zero", a)) }
-		}
+		if n % 2 == 1 { number = number * base }
+		base = base * base;
+		n /= 2;
 	}
+	number
 }
 
 pub fn sqrt(n: f64) -> f64
@@ -186,5 +324,5 @@ pub fn sqrt(n: f64) -> f64
 
 pub fn abs(n: f64) -> f64
 {
-	if n < 0.0 { n * -1.0 } else { n }
+	if n < 0.0 { -n } else { n }
 }
\ No newline at end of file
@@ -1,81 +1,333 @@
 use std::env;
+use std::fs::File;
+use std::io::{self, BufReader};
 use std::process::exit;
 
-use computor::{parser, solver};
-use computor::{abs};
-use computor::{Token, Comparison};
+use computor::{forms, json, numeric, parser, solver, system};
+use computor::{Comparison, ComputorError, Latex, LinearEquation, Method, Polynomial, Rational, Scalar, Solution, StepLog, Verbosity};
+use computor::numeric::Residual;
+use computor::{format_number, latex_number};
 
-fn exit_error(msg: &str)
+mod batch;
+mod repl;
+
+// The settings given on the command line, some of them can be changed in the REPL
+struct Options
+{
+	// Compute with fractions instead of floating point numbers
+	exact: bool,
+
+	// Only the solution, the steps of the solver, or also the reduction of the expression
+	verbosity: Verbosity,
+
+	// Evaluate the polynomial at each root and print the residuals
+	verify: bool,
+
+	// How the equations of degree 2 are explained
+	method: Method,
+
+	format: Format
+}
+
+// The output of the solver
+#[derive(PartialEq)]
+enum Format
+{
+	Text,
+	// One object per expression
+	Json,
+	// A gather* environment to paste in a document
+	Latex
+}
+
+fn exit_error(msg: &str) -> !
 {
 	eprintln!("{}", msg);
 	exit(1);
 }
 
-fn polynomial_degree(coef: &Vec<f64>) -> u8
+fn polynomial_degree<T: Scalar>(poly: &Polynomial<T>) -> u32
+{
+	poly.degree()
+}
+
+fn print_reduced_form<T: Scalar>(poly: &Polynomial<T>, cmp: &Comparison, variable: &str)
+{
+	print!("Reduced form: {}", poly.display_with(variable));
+	if *cmp != Comparison::No
+	{
+		print!(" {} 0", cmp);
+	}
+	println!();
+}
+
+// The factored form when the roots are real, the vertex form and the vertex of a quadratic
+// ex:	Factored form: 2(x + 3)(x - 1) = 0
+//		Vertex form: 2(x + 1)² - 8
+fn print_quadratic_forms<T: Scalar>(poly: &Polynomial<T>, cmp: &Comparison, variable: &str)
 {
-	for (i, c) in coef.iter().enumerate()
+	if let Some(factored) = forms::factored_form(poly, variable)
 	{
-		if *c != 0.0
+		print!("Factored form: {}", factored);
+		if *cmp != Comparison::No
 		{
-			return 2 - i as u8;
+			print!(" {} 0", cmp);
 		}
+		println!();
+	}
+	if let (Some(form), Some((h, k))) = (forms::vertex_form(poly, variable), forms::vertex(poly))
+	{
+		println!("Vertex form: {}", form);
+		println!("Vertex: ({}, {})", h.format(), k.format());
+		println!("Axis of symmetry: {} = {}", variable, h.format());
 	}
-	return 0;
 }
 
-fn print_reduced_form(coef: &Vec<f64>, cmp: &Comparison)
+fn print_latex<T: Scalar>(poly: &Polynomial<T>, cmp: &Comparison, variable: &str, solution: Option<&Solution>, residuals: &[Residual])
 {
-	print!("Reduced form:");
-	let mut to_display = String::new();
-	for (i, n) in coef.iter().enumerate()
+	let mut lines = match solution
+	{
+		Some(solution) => vec![format!("{} {} 0", poly.latex_with(variable), cmp.latex()), solution.latex()],
+		None => vec![poly.latex_with(variable)]
+	};
+	// ex: \left|P\left(2\right)\right| = 0
+	for r in residuals
 	{
-		if *n == 0.0 && i != 2 { continue }
-		if to_display.len() == 0
+		lines.push(format!("\\left|P\\left({}\\right)\\right| = {}", r.root.latex(), latex_number(&format_number(r.residual))));
+	}
+	println!("\\begin{{gather*}}");
+	println!("{}", lines.join(" \\\\\n"));
+	println!("\\end{{gather*}}");
+}
+
+// The residual |P(r)| of each distinct root, a doubtful root is flagged
+// ex: |P(-1.00e-8)| = 1.65e-24
+fn print_residuals(residuals: &[Residual])
+{
+	println!("Verification with Horner's scheme:");
+	for r in residuals
+	{
+		match r.doubtful
 		{
-			to_display.push_str(&format!(" {}", Token::Var((*n, 2 - i as u8))));
-			continue;
+			true => println!("|P({})| = {}\tWarning: the residual is too large ({} relative to the terms of P, above {:e})",
+				r.root, format_number(r.residual), format_number(r.relative), numeric::VERIFY_TOLERANCE),
+			false => println!("|P({})| = {}", r.root, format_number(r.residual))
 		}
-		match *n >= 0.0
+	}
+}
+
+// The residuals of the distinct roots when they are verified, a multiple root is checked once
+fn residuals<T: Scalar>(poly: &Polynomial<T>, solution: Option<&Solution>, options: &Options) -> Vec<Residual>
+{
+	match (solution, options.verify)
+	{
+		(Some(solution), true) =>
 		{
-			true => to_display.push_str(&format!(" + {}", Token::Var((*n, 2 - i as u8)))),
-			false => to_display.push_str(&format!(" - {}", Token::Var((abs(*n), 2 - i as u8))))
+			let mut roots = solution.roots.clone();
+			roots.dedup();
+			numeric::verify_roots(&poly.to_f64(), &roots)
 		}
+		_ => vec![]
 	}
-	print!("{}", to_display);
-	if *cmp != Comparison::No
+}
+
+// T is f64, or Rational in exact mode
+fn compute_expression<T: Scalar>(expression: &str, options: &Options) -> Result<(), ComputorError>
+{
+	let (tokens, comparison_token, variable) = parser::get_tokens::<T>(expression)?;
+	let mut log = StepLog::new(options.verbosity);
+	let poly = parser::get_coefficients(&tokens, &variable, &mut log)?;
+	if options.format != Format::Text
 	{
-		print!(" {} 0", cmp);
+		let solution = match comparison_token
+		{
+			Comparison::No => None,
+			_ => Some(solver::get_solution(&poly, poly.degree(), comparison_token.clone(), &variable, options.method, options.verbosity)?)
+		};
+		let residuals = residuals(&poly, solution.as_ref(), options);
+		match options.format
+		{
+			Format::Json =>
+			{
+				let residuals = if options.verify { Some(residuals.as_slice()) } else { None };
+				println!("{}", json::report(expression, &poly, &comparison_token, &variable, solution.as_ref(), residuals));
+			}
+			_ => print_latex(&poly, &comparison_token, &variable, solution.as_ref(), &residuals)
+		}
+		return Ok(())
 	}
-	println!("");
+	for step in log.lines()
+	{
+		println!("{}", step);
+	}
+	let quiet = options.verbosity == Verbosity::Quiet;
+
+	// Without a comparison the reduced form is the solution
+	if !quiet || comparison_token == Comparison::No
+	{
+		print_reduced_form(&poly, &comparison_token, &variable);
+	}
+	if !quiet { print_quadratic_forms(&poly, &comparison_token, &variable) }
+	let degree = polynomial_degree(&poly);
+	if comparison_token != Comparison::No
+	{
+		if !quiet { println!("Polynomial degree: {}", degree) }
+		let solution = solver::get_solution(&poly, degree, comparison_token, &variable, options.method, options.verbosity)?;
+		println!("{}", solution);
+		let residuals = residuals(&poly, Some(&solution), options);
+		if !residuals.is_empty() { print_residuals(&residuals) }
+	}
+	Ok(())
 }
 
-fn compute_expression(expression: String) -> Result<(), String>
+// The equations of a linear system separated by ';'
+fn compute_system<T: Scalar>(expression: &str, options: &Options) -> Result<(), ComputorError>
 {
-	let (tokens, comparison_token) = parser::get_tokens(&expression)?;
-	let tokens = parser::reduce_tokens(tokens)?;
-	let coef = parser::get_coefficients(&tokens);
-	print_reduced_form(&coef, &comparison_token);
-	let degree = polynomial_degree(&coef);
-	if comparison_token != Comparison::No
+	let (equations, names) = parser::get_system_tokens::<T>(expression)?;
+	let equations = equations.iter()
+		.map(|tokens| parser::get_linear_coefficients(tokens, &names))
+		.collect::<Result<Vec<LinearEquation<T>>, ComputorError>>()?;
+	let solution = system::solve_system(&equations)?;
+	match options.format
 	{
-		println!("Polynomial degree: {}", degree);
-		solver::get_solution(&coef, degree, comparison_token);
+		Format::Json => println!("{}", json::system_report(expression, &solution)),
+		Format::Latex =>
+		{
+			let equations: Vec<String> = equations.iter().map(|equation| equation.latex()).collect();
+			println!("\\begin{{gather*}}");
+			println!("\\begin{{cases}} {} \\end{{cases}} \\\\\n{}", equations.join(" \\\\ "), solution.latex());
+			println!("\\end{{gather*}}");
+		}
+		Format::Text =>
+		{
+			if options.verbosity == Verbosity::Quiet
+			{
+				println!("{}", solution.conclusion());
+				return Ok(())
+			}
+			println!("Reduced system:");
+			for equation in &equations
+			{
+				println!("  {}", equation);
+			}
+			println!("Unknowns: {}", solution.unknowns.join(", "));
+			println!("{}", solution);
+		}
 	}
 	Ok(())
 }
 
+// Solve an expression with the number type chosen by the options, a ';' separates the equations of a system
+fn solve(expression: &str, options: &Options) -> Result<(), ComputorError>
+{
+	match (expression.contains(';'), options.exact)
+	{
+		(true, true) => compute_system::<Rational>(expression, options),
+		(true, false) => compute_system::<f64>(expression, options),
+		(false, true) => compute_expression::<Rational>(expression, options),
+		(false, false) => compute_expression::<f64>(expression, options)
+	}
+}
+
+// The error as displayed in the chosen format
+fn error_message(error: &ComputorError, expression: &str, options: &Options) -> String
+{
+	match options.format
+	{
+		Format::Text | Format::Latex => error.render(expression),
+		Format::Json => json::error_report(expression, error)
+	}
+}
+
 fn main()
 {
-	let args: Vec<String> = env::args().collect();
+	let mut options = Options { exact: false, verbosity: Verbosity::Normal, verify: false, method: Method::Discriminant, format: Format::Text };
+	let mut interactive = false;
+	let mut expressions: Vec<String> = vec![];
 
-	if args.len() != 2
+	// The file of the batch mode, "-" is the standard input
+	let mut file: Option<String> = None;
+
+	let mut args = env::args().skip(1);
+	while let Some(arg) = args.next()
 	{
-		exit_error("Error: invalid number of arguments");
+		match arg.as_str()
+		{
+			"--exact" => options.exact = true,
+			"--repl" => interactive = true,
+			"--quiet" => options.verbosity = Verbosity::Quiet,
+			"--explain" => options.verbosity = Verbosity::Explain,
+			"--verify" => options.verify = true,
+			"--file" => match args.next()
+			{
+				Some(path) => file = Some(path),
+				None => exit_error("Error: '--file' expects a file name")
+			},
+			"--method" => match args.next().as_deref()
+			{
+				Some("discriminant") => options.method = Method::Discriminant,
+				Some("square") => options.method = Method::Square,
+				_ => exit_error("Error: '--method' expects 'discriminant' or 'square'")
+			},
+			"--format" => match args.next().as_deref()
+			{
+				Some("text") => options.format = Format::Text,
+				Some("json") => options.format = Format::Json,
+				Some("latex") => options.format = Format::Latex,
+				_ => exit_error("Error: '--format' expects 'text', 'json' or 'latex'")
+			},
+			"-" => file = Some(arg),
+			_ if arg.starts_with("--") => exit_error(&format!("Error: unknown option '{}'", arg)),
+			_ => expressions.push(arg)
+		}
 	}
 
-	let expr = args[1].to_owned();
+	// Solve each line of a file, the exit status tells if any of them failed
+	if let Some(path) = file
+	{
+		if interactive || !expressions.is_empty()
+		{
+			exit_error("Error: the batch mode does not take an expression");
+		}
+		let failed = match path.as_str()
+		{
+			"-" => batch::run(io::stdin().lock(), &options),
+			_ => match File::open(&path)
+			{
+				Ok(f) => batch::run(BufReader::new(f), &options),
+				Err(e) => exit_error(&format!("Error: cannot read '{}': {}", path, e))
+			}
+		};
+		exit(if failed > 0 { 1 } else { 0 });
+	}
+
+	// Without any expression the equations are read in a loop
+	if interactive || expressions.is_empty()
+	{
+		if !expressions.is_empty()
+		{
+			exit_error("Error: the REPL does not take an expression");
+		}
+		repl::run(&mut options);
+		return
+	}
+
+	// Several expressions are the equations of a system
+	let expr = expressions.join("; ");
 
 	if expr.is_empty() { exit_error("Error: the expression must not be empty") }
 
-	if let Err(e) = compute_expression(expr) { exit_error(&e) }
+	if let Err(e) = solve(&expr, &options)
+	{
+		// The JSON error is part of the output
+		match options.format
+		{
+			Format::Text | Format::Latex => exit_error(&e.render(&expr)),
+			Format::Json =>
+			{
+				println!("{}", error_message(&e, &expr, &options));
+				exit(1);
+			}
+		}
+	}
 }
@@ -0,0 +1,378 @@
+use crate::{Complex, Polynomial};
+use crate::abs;
+
+// Stop iterating when no root moves by more than TOLERANCE (relative to its modulus)
+pub const TOLERANCE: f64 = 1e-12;
+pub const MAX_ITERATIONS: u32 = 1000;
+
+// A root whose imaginary part is smaller than this (relative to its modulus) is considered real
+const REAL_THRESHOLD: f64 = 1e-7;
+
+// Approximations closer than this (relative to their modulus) may be the same multiple root
+const CLUSTER_RADIUS: f64 = 1e-2;
+
+// A root is doubtful when |P(root)| is larger than this, relative to the terms of P that cancel out
+pub const VERIFY_TOLERANCE: f64 = 1e-9;
+
+// The result of a numeric root finding where residuals[i] = |P(roots[i])|
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericRoots
+{
+	pub roots: Vec<Complex>,
+	pub residuals: Vec<f64>,
+	pub iterations: u32,
+	pub converged: bool
+}
+
+// The value of P at a reported root. relative is |P(root)| divided by the sum of |a[i]| * |root|^i,
+// the rounding error of the evaluation is about ε times this sum
+#[derive(Debug, Clone, PartialEq)]
+pub struct Residual
+{
+	pub root: Complex,
+	pub residual: f64,
+	pub relative: f64,
+
+	// True when the relative residual is above VERIFY_TOLERANCE and the residual above the rounding error of the coefficients
+	pub doubtful: bool
+}
+
+// Evaluate the polynomial at a complex point with Horner's scheme
+pub fn eval(poly: &Polynomial, z: Complex) -> Complex
+{
+	poly.coefficients().iter().rev().fold(Complex::default(), |result, n| result * z + Complex::real(*n))
+}
+
+// The sum of |a[i]| * r^i, a bound of the rounding error made when evaluating P at |z| = r
+fn eval_bound(poly: &Polynomial, r: f64) -> f64
+{
+	poly.coefficients().iter().rev().fold(0.0, |sum, n| sum * r + abs(*n))
+}
+
+// Every root lies in the disk of radius 1 + max|a[i] / a[n]| (Cauchy bound)
+pub fn root_bound(poly: &Polynomial) -> f64
+{
+	let degree = poly.degree();
+	let lead = poly.coefficient(degree);
+	1.0 + poly.coefficients()[..degree as usize].iter().fold(0.0, |max: f64, n| max.max(abs(n / lead)))
+}
+
+// Every root lies in the disk of radius 2 max(|a[n-1] / a[n]|, |a[n-2] / a[n]|^(1/2), ..., |a[0] / 2a[n]|^(1/n))
+// (Fujiwara bound), it is much tighter than the Cauchy bound when the coefficients are large
+// ex: x⁵ - 1e15 => 2 × (5e14)^(1/5) = 1741, the roots have a modulus of 1000
+pub fn fujiwara_bound(poly: &Polynomial) -> f64
+{
+	let degree = poly.degree() as usize;
+	let coefficients = poly.coefficients();
+	let lead = abs(coefficients[degree]);
+	let largest = (1..=degree).fold(0.0, |max: f64, k|
+	{
+		let n = if k == degree { abs(coefficients[0]) / lead / 2.0 } else { abs(coefficients[degree - k]) / lead };
+		max.max(n.powf(1.0 / k as f64))
+	});
+	2.0 * largest
+}
+
+// The roots other than 0 are outside of the disk of radius |a[k]| / (|a[k]| + max|a[i]|)
+// where a[k] is the first coefficient that is not null
+// ex: x⁶ - x³ - 0.5x => 0.5 / 1.5, its roots are 0 and roots larger than 1 / 3
+fn null_bound(poly: &Polynomial) -> f64
+{
+	let coefficients = poly.coefficients();
+	match coefficients.iter().position(|n| *n != 0.0)
+	{
+		Some(k) =>
+		{
+			let first = abs(coefficients[k]);
+			first / (first + coefficients[k + 1..].iter().fold(0.0, |max: f64, n| max.max(abs(*n))))
+		}
+		None => 0.0
+	}
+}
+
+// A root computed with a closed form is only known up to the rounding noise of the bound of the roots of poly:
+// a root below it is polished with Newton's method, then it is 0 when 0 may be a root of poly,
+// otherwise only its parts negligible next to its modulus are noise
+// ex: 7.07e-17 among the roots of x³ - x, whose bound is 2, is 0 but 1.74e17 among those of x⁴ + 1e100x + 1 is -1e-100
+pub fn clean_root(root: Complex, poly: &Polynomial) -> Complex
+{
+	let (noise, null) = (TOLERANCE * root_bound(poly), TOLERANCE * null_bound(poly));
+	let (root, noise) = match root.modulus() < noise
+	{
+		true =>
+		{
+			let root = newton(poly, root, TOLERANCE, null);
+			(root, TOLERANCE * root.modulus())
+		}
+		false => (root, noise)
+	};
+	if root.modulus() < null
+	{
+		return Complex::default();
+	}
+	let clean = |n: f64| if abs(n) < noise { 0.0 } else { n };
+	Complex::new(clean(root.re), clean(root.im))
+}
+
+// Polish an approximation of a simple root of poly with Newton's method
+// The steps are relative to the modulus of the root, or to size when it is smaller
+pub fn newton(poly: &Polynomial, z: Complex, tolerance: f64, size: f64) -> Complex
+{
+	let derivative = poly.derivative();
+	let mut z = z;
+	for _ in 0..MAX_ITERATIONS
+	{
+		let step = eval(poly, z) / eval(&derivative, z);
+		if !step.is_finite() { break }
+		z = z - step;
+		if step.modulus() <= tolerance * z.modulus().max(size) { break }
+	}
+	z
+}
+
+// A root of multiplicity m is only found with a precision of about ε^(1/m): the m approximations
+// scatter around it. It is a simple root of the (m - 1)th derivative of P so it is polished from
+// the mean of the cluster, then kept when it is a better root than the worst approximation, which
+// is not the case for distinct but close roots.
+fn merge_clusters(poly: &Polynomial, roots: &mut [Complex])
+{
+	let mut merged = vec![false; roots.len()];
+	for i in 0..roots.len()
+	{
+		if merged[i] { continue }
+		let radius = CLUSTER_RADIUS * roots[i].modulus().max(1.0);
+		let cluster: Vec<usize> = (i..roots.len())
+			.filter(|j| !merged[*j] && (roots[i] - roots[*j]).modulus() < radius)
+			.collect();
+		if cluster.len() < 2 { continue }
+		let size = Complex::real(cluster.len() as f64);
+		let mean = cluster.iter().fold(Complex::default(), |sum, j| sum + roots[*j] / size);
+		let derivative = (1..cluster.len()).fold(poly.clone(), |d, _| d.derivative());
+		let mean = newton(&derivative, mean, TOLERANCE, 1.0);
+		let residual = eval(poly, mean).modulus();
+		if cluster.iter().any(|j| residual <= eval(poly, roots[*j]).modulus())
+		{
+			for j in cluster
+			{
+				roots[j] = mean;
+				merged[j] = true;
+			}
+		}
+	}
+}
+
+// Find every complex root at once with the Durand-Kerner method:
+// each approximation z[i] is moved by P(z[i]) / ∏(z[i] - z[j]) for j != i
+pub fn durand_kerner(poly: &Polynomial, tolerance: f64, max_iterations: u32) -> NumericRoots
+{
+	let degree = poly.degree() as usize;
+	let lead = poly.coefficient(poly.degree());
+	let monic = Polynomial::from_coefficients(poly.coefficients().iter().map(|n| n / lead).collect());
+
+	// Start from points spread on a circle inside the bound of the roots, P can be evaluated there without overflowing
+	// The 0.4 offset avoids starting on a symmetry axis
+	let radius = fujiwara_bound(&monic) / 2.0;
+	let mut roots: Vec<Complex> = (0..degree).map(|k|
+	{
+		let angle = 2.0 * std::f64::consts::PI * k as f64 / degree as f64 + 0.4;
+		Complex::new(radius * angle.cos(), radius * angle.sin())
+	}).collect();
+
+	// The steps are relative to the roots, or to the radius under which 0 is the only root
+	// ex: the roots of 1e80x⁴ + x - 1 are about 1e-20, a step of 1e-13 is not small
+	let null = null_bound(&monic);
+	let mut iterations = 0;
+	let mut converged = false;
+	while iterations < max_iterations && !converged
+	{
+		iterations += 1;
+		converged = true;
+		for i in 0..degree
+		{
+			let mut denominator = Complex::real(1.0);
+			for j in 0..degree
+			{
+				if i != j { denominator = denominator * (roots[i] - roots[j]) }
+			}
+			let value = eval(&monic, roots[i]);
+
+			// The root is as precise as the floating point evaluation of P allows
+			if value.modulus() <= f64::EPSILON * eval_bound(&monic, roots[i].modulus()) { continue }
+			let step = value / denominator;
+			// The approximation can't move, the method has not converged
+			if !step.is_finite()
+			{
+				converged = false;
+				continue;
+			}
+			roots[i] = roots[i] - step;
+			if step.modulus() > tolerance * roots[i].modulus().max(null)
+			{
+				converged = false;
+			}
+		}
+	}
+
+	merge_clusters(&monic, &mut roots);
+
+	// Remove the rounding noise of the null roots, of the real roots and of the null parts,
+	// a part is compared to the modulus of its root, ex: the real part of 1000i is noise but not 1000
+	let null = tolerance * null_bound(&monic);
+	for root in roots.iter_mut()
+	{
+		if root.modulus() < null { *root = Complex::default() }
+		if abs(root.im) < REAL_THRESHOLD * root.modulus() { root.im = 0.0 }
+		if abs(root.re) < tolerance * root.modulus() { root.re = 0.0 }
+	}
+	roots.sort_by(|s1, s2| (!s1.is_real()).cmp(&!s2.is_real()).then(s1.re.total_cmp(&s2.re)).then(s1.im.total_cmp(&s2.im)));
+	let residuals = roots.iter().map(|root| eval(poly, *root).modulus()).collect();
+	NumericRoots { roots, residuals, iterations, converged }
+}
+
+// Check the roots by evaluating P at each of them with Horner's scheme
+// ex: x² - 2 at 1.41 => residual 1.19e-2, relative 3.0e-3, doubtful
+pub fn verify_roots(poly: &Polynomial, roots: &[Complex]) -> Vec<Residual>
+{
+	// Near a root close to 0, the terms of P are tiny and any rounding error is large next to them
+	// ex: 2x³ - x at 5.55e-17 => residual 1.11e-16, relative 1
+	let floor = f64::EPSILON * poly.coefficients().iter().fold(0.0, |max: f64, n| max.max(abs(*n)));
+	roots.iter().map(|root|
+	{
+		let residual = eval(poly, *root).modulus();
+		let relative = match residual == 0.0
+		{
+			true => 0.0,
+			false => residual / eval_bound(poly, root.modulus())
+		};
+		let doubtful = (relative > VERIFY_TOLERANCE && residual > floor) || relative.is_nan();
+		Residual { root: *root, residual, relative, doubtful }
+	}).collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+
+	// The polynomial with these real roots
+	fn with_roots(roots: &[f64]) -> Polynomial
+	{
+		roots.iter().fold(Polynomial::from_coefficients(vec![1.0]), |poly, r| poly * Polynomial::from_coefficients(vec![-r, 1.0]))
+	}
+
+	fn assert_close(found: &[Complex], expected: &[Complex], tolerance: f64)
+	{
+		assert_eq!(found.len(), expected.len(), "{:?}", found);
+		for (root, expected) in found.iter().zip(expected)
+		{
+			assert!((*root - *expected).modulus() < tolerance, "{:?} != {:?}", found, expected);
+		}
+	}
+
+	#[test]
+	fn durand_kerner_real_roots()
+	{
+		let poly = with_roots(&[-3.0, -1.0, 0.5, 2.0, 4.0]);
+		let result = durand_kerner(&poly, TOLERANCE, MAX_ITERATIONS);
+		assert!(result.converged);
+		let expected: Vec<Complex> = [-3.0, -1.0, 0.5, 2.0, 4.0].iter().map(|n| Complex::real(*n)).collect();
+		assert_close(&result.roots, &expected, 1e-9);
+		assert!(result.roots.iter().all(|root| root.im == 0.0), "{:?}", result.roots);
+		assert!(result.residuals.iter().all(|r| *r < 1e-9), "{:?}", result.residuals);
+	}
+
+	#[test]
+	fn durand_kerner_complex_roots()
+	{
+		// x⁵ - 1 = 0, the fifth roots of unity
+		let poly = Polynomial::from_coefficients(vec![-1.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+		let result = durand_kerner(&poly, TOLERANCE, MAX_ITERATIONS);
+		assert!(result.converged);
+		assert_eq!(result.roots[0], Complex::real(1.0));
+		assert!(result.roots.iter().all(|root| (root.modulus() - 1.0).abs() < 1e-9), "{:?}", result.roots);
+		// The complex roots come in conjugate pairs
+		assert_eq!(result.roots.iter().filter(|root| !root.is_real()).count(), 4);
+		assert!(result.roots.iter().all(|root| result.roots.iter().any(|other| (*other - root.conjugate()).modulus() < 1e-9)));
+	}
+
+	#[test]
+	fn durand_kerner_multiple_roots()
+	{
+		// (x - 1)³(x + 2)(x - 3) = 0, the approximations of the triple root are merged
+		let poly = with_roots(&[1.0, 1.0, 1.0, -2.0, 3.0]);
+		let result = durand_kerner(&poly, TOLERANCE, MAX_ITERATIONS);
+		let expected: Vec<Complex> = [-2.0, 1.0, 1.0, 1.0, 3.0].iter().map(|n| Complex::real(*n)).collect();
+		assert_close(&result.roots, &expected, 1e-6);
+	}
+
+	#[test]
+	fn durand_kerner_large_constant()
+	{
+		// x⁵ - 1e15 = 0, the real root 1000 is not noise next to the bound of the roots
+		let poly = Polynomial::from_coefficients(vec![-1e15, 0.0, 0.0, 0.0, 0.0, 1.0]);
+		let result = durand_kerner(&poly, TOLERANCE, MAX_ITERATIONS);
+		assert!(result.converged);
+		assert_eq!(result.roots.iter().filter(|root| root.is_real()).count(), 1, "{:?}", result.roots);
+		assert!((result.roots[0].re - 1000.0).abs() < 1e-9, "{:?}", result.roots);
+		// x⁶ - 2e14 = 0 keeps its six roots on the circle of radius 2e14^(1/6)
+		let poly = Polynomial::from_coefficients(vec![-2e14, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+		let result = durand_kerner(&poly, TOLERANCE, MAX_ITERATIONS);
+		let radius = 2e14_f64.powf(1.0 / 6.0);
+		assert!(result.roots.iter().all(|root| (root.modulus() / radius - 1.0).abs() < 1e-9), "{:?}", result.roots);
+		assert_eq!(result.roots.iter().filter(|root| root.is_real()).count(), 2, "{:?}", result.roots);
+	}
+
+	#[test]
+	fn durand_kerner_huge_constant()
+	{
+		// x⁵ - 1e40 = 0 and x⁵⁰ - 1e300 = 0 can't be evaluated on the circle of the Cauchy bound
+		for (degree, constant, radius) in [(5, 1e40, 1e8), (50, 1e300, 1e6)]
+		{
+			let mut coefficients = vec![0.0; degree + 1];
+			coefficients[0] = -constant;
+			coefficients[degree] = 1.0;
+			let result = durand_kerner(&Polynomial::from_coefficients(coefficients), TOLERANCE, MAX_ITERATIONS);
+			assert!(result.converged);
+			assert!(result.roots.iter().all(|root| (root.modulus() / radius - 1.0).abs() < 1e-9), "{:?}", result.roots);
+			assert!(result.residuals.iter().all(|r| r.is_finite()), "{:?}", result.residuals);
+		}
+	}
+
+	#[test]
+	fn durand_kerner_small_roots()
+	{
+		// 1e80x⁴ + x - 1 = 0 has roots of about 1e-20
+		let poly = Polynomial::from_coefficients(vec![-1.0, 1.0, 0.0, 0.0, 1e80]);
+		let result = durand_kerner(&poly, TOLERANCE, MAX_ITERATIONS);
+		assert!(result.converged);
+		assert!(result.roots.iter().all(|root| (root.modulus() / 1e-20 - 1.0).abs() < 1e-9), "{:?}", result.roots);
+		assert!(verify_roots(&poly, &result.roots).iter().all(|r| !r.doubtful), "{:?}", result.residuals);
+	}
+
+	#[test]
+	fn durand_kerner_null_root()
+	{
+		// x⁵ - x = 0 has the root 0 exactly
+		let poly = Polynomial::from_coefficients(vec![0.0, -1.0, 0.0, 0.0, 0.0, 1.0]);
+		let result = durand_kerner(&poly, TOLERANCE, MAX_ITERATIONS);
+		assert!(result.roots.contains(&Complex::real(0.0)), "{:?}", result.roots);
+	}
+
+	#[test]
+	fn verify_roots_near_zero()
+	{
+		// 2x³ - x = 0
+		let poly = Polynomial::from_coefficients(vec![0.0, -1.0, 0.0, 2.0]);
+		let residuals = verify_roots(&poly, &[Complex::real(5.55e-17), Complex::real(0.5_f64.sqrt())]);
+		assert!(residuals.iter().all(|r| !r.doubtful), "{:?}", residuals);
+	}
+
+	#[test]
+	fn verify_roots_doubtful()
+	{
+		// x² - 2 = 0
+		let poly = Polynomial::from_coefficients(vec![-2.0, 0.0, 1.0]);
+		let residuals = verify_roots(&poly, &[Complex::real(1.41), Complex::real(-(2.0_f64.sqrt()))]);
+		assert!(residuals[0].doubtful && !residuals[1].doubtful, "{:?}", residuals);
+	}
+}
@@ -1,163 +1,257 @@
 use std::iter::Peekable;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
-use crate::{Token, Comparison, Operator};
-use crate::{pow, do_op};
+use crate::{Token, SpannedToken, Comparison, Operator, Polynomial, Scalar};
+use crate::pow;
+use crate::error::{ComputorError, SyntaxKind, Span};
+use crate::expr::{to_linear, to_polynomial, Expr, SpannedExpr};
+use crate::polynomial::MAX_DEGREE;
+use crate::steps::{StepLog, Verbosity};
+use crate::system::LinearEquation;
 
-pub fn skip_spaces<T: Iterator<Item=char>>(chars: &mut Peekable<T>)
+// The names of the unknowns in the order they are read
+// An equation has a single unknown, a system can have several of them
+struct Unknowns
 {
-	while let Some(next) = chars.peek()
+	names: Vec<String>,
+	system: bool
+}
+
+// The tokens of each equation of a system
+pub type SystemTokens<N> = Vec<Vec<SpannedToken<N>>>;
+
+// The lexer reads the expression as (position, char) so that errors can point to it
+pub fn skip_spaces<T: Iterator<Item=(usize, char)>>(chars: &mut Peekable<T>)
+{
+	while let Some((_, next)) = chars.peek()
 	{
 		if *next != ' ' && *next != '\t' { break }
 		chars.next();
 	}
 }
 
-pub fn get_exponent<T: Iterator<Item=char>>(chars: &mut Peekable<T>, token_list: &HashSet<char>) -> Result<u8, String>
+// A variable name is made of letters, ex: x | t | speed
+fn is_letter(c: char) -> bool
+{
+	c.is_alphabetic() || c == '_'
+}
+
+// A char that can follow a number or a variable
+fn is_valid(c: char, token_list: &HashSet<char>) -> bool
+{
+	token_list.contains(&c) || is_letter(c)
+}
+
+// Returns the exponent read after the '^' at position caret and the span of its digits
+pub fn get_exponent<T: Iterator<Item=(usize, char)>>(caret: usize, chars: &mut Peekable<T>, token_list: &HashSet<char>) -> Result<(u32, Span), ComputorError>
 {
 	let mut str_number = String::new();
+	let mut span = Span::at(caret);
 	skip_spaces(chars);
-	while let Some(next) = chars.peek()
+	while let Some((pos, next)) = chars.peek()
 	{
 		match next
 		{
-			'0'...'9' | '-' => str_number.push(*next),
-			_ if !token_list.contains(next) =>
+			'0'..='9' => str_number.push(*next),
+			// Only keep a sign at the start so that "x^2-1" is read as "x^2 - 1"
+			'-' if str_number.is_empty() => str_number.push(*next),
+			_ if !is_valid(*next, token_list) =>
 			{
-				return Err(format!("Lexical error: '{}' is not a valid token", next))
+				return Err(ComputorError::Lexical { ch: *next, pos: *pos })
 			}
 			_ => break
 		};
+		if str_number.len() == 1 { span.start = *pos }
+		span.end = pos + 1;
 		chars.next();
 	}
-	let number = match str_number.parse::<u8>()
+	if str_number.is_empty()
+	{
+		return Err(ComputorError::syntax(SyntaxKind::ExpectedExponent, Some(span)));
+	}
+	let number = match str_number.parse::<u32>()
 	{
 		Ok(n) => Ok(n),
-		Err(_) => Err(format!("Syntax error: '{}' is not a valid exponent", str_number))
+		Err(_) => Err(ComputorError::InvalidExponent { exponent: str_number, span: Some(span) })
 	}?;
-	Ok(number)
+	Ok((number, span))
 }
 
-pub fn get_number<T: Iterator<Item=char>>(c: char, chars: &mut Peekable<T>, token_list: &HashSet<char>) -> Result<Token, String>
+fn get_number<N: Scalar, T: Iterator<Item=(usize, char)>>(start: usize, c: char, chars: &mut Peekable<T>, token_list: &HashSet<char>, unknowns: &mut Unknowns) -> Result<SpannedToken<N>, ComputorError>
 {
     let mut str_number = String::new();
+	let mut end = start + 1;
 	str_number.push(c);
-	while let Some(next) = chars.peek()
+	while let Some((pos, next)) = chars.peek()
 	{
 		match next
 		{
-			'0'...'9' | '.' => str_number.push(*next),
-			_ if !token_list.contains(next) =>
+			'0'..='9' | '.' => str_number.push(*next),
+			_ if !is_valid(*next, token_list) =>
 			{
-				return Err(format!("Lexical error: '{}' is not a valid token", next))
+				return Err(ComputorError::Lexical { ch: *next, pos: *pos })
 			}
 			_ => break
 		};
+		end = pos + 1;
 		chars.next();
 	}
+	let span = Some(Span::new(start, end));
 	if let Some('.') = str_number.chars().last()
 	{
-		return Err(format!("Syntax error: '{}' is not a valid number", str_number));
+		return Err(ComputorError::syntax(SyntaxKind::InvalidNumber(str_number), span));
 	}
-	let number = match str_number.parse::<f64>()
+	let number: N = match N::from_literal(&str_number)
 	{
-		Ok(n) => Ok(n),
-		Err(_) => Err(format!("Syntax error: '{}' is not a valid number", str_number))
+		Some(n) => Ok(n),
+		None => Err(ComputorError::syntax(SyntaxKind::InvalidNumber(str_number), span))
 	}?;
 	skip_spaces(chars);
-	if let Some('^') = chars.peek()
+	if let Some((pos, '^')) = chars.peek()
 	{
+		let caret = *pos;
 		chars.next();
-		let power = get_exponent(chars, token_list)?;
-		return Ok(Token::Var((pow(number, power), 0)));
+		let (power, span) = get_exponent(caret, chars, token_list)?;
+		return Ok((Token::Var((pow(number, power), 0)), Span::new(start, span.end)));
 	}
-	if let Some('²') = chars.peek()
+	if let Some((pos, '²')) = chars.peek()
 	{
+		let end = pos + 1;
 		chars.next();
-		return Ok(Token::Var((pow(number, 2), 0)));
+		return Ok((Token::Var((pow(number, 2), 0)), Span::new(start, end)));
 	}
-	if let Some(c) = chars.peek()
+	if let Some(&(pos, c)) = chars.peek()
 	{
-		if *c == 'x' || *c == 'X'
+		if is_letter(c)
 		{
 			chars.next();
-			if let Token::Var((n, p)) = get_var_exponent(chars, token_list)?
+			if let (Token::Var((n, p)), span) = get_variable(pos, c, chars, token_list, unknowns)?
 			{
-				return Ok(Token::Var((number * n, p)));
+				return Ok((Token::Var((number * n, p)), Span::new(start, span.end)));
 			}
 		}
 	}
-	Ok(Token::Var((number, 0)))
+	Ok((Token::Var((number, 0)), Span::new(start, end)))
 }
 
-pub fn get_var_exponent<T: Iterator<Item=char>>(chars: &mut Peekable<T>, token_list: &HashSet<char>) -> Result<Token, String>
+// Read the name of a variable starting with the letter c at position start, 'x' and 'X' are the same unknown
+// In an equation it must be the same unknown as the other variables of the expression
+// Returns the span of the name and the index of its unknown from 1
+fn get_unknown<T: Iterator<Item=(usize, char)>>(start: usize, c: char, chars: &mut Peekable<T>, unknowns: &mut Unknowns) -> Result<(Span, u32), ComputorError>
 {
-	skip_spaces(chars);
-	while let Some(next) = chars.peek()
+	let mut name = c.to_string();
+	let mut span = Span::at(start);
+	while let Some(&(pos, next)) = chars.peek()
 	{
-		match next
+		if !is_letter(next) { break }
+		name.push(next);
+		span.end = pos + 1;
+		chars.next();
+	}
+	if name == "X" { name = "x".to_string() }
+	match (unknowns.names.iter().position(|n| *n == name), unknowns.names.first())
+	{
+		(Some(i), _) => Ok((span, i as u32 + 1)),
+		(None, Some(first)) if !unknowns.system =>
 		{
-			'²' => { chars.next(); return Ok(Token::Var((1.0, 2))) },
-			'^' => { chars.next(); break },
-			_ if token_list.contains(next) => return Ok(Token::Var((1.0, 1))),
-			_ => return Err(format!("Lexical error: '{}' is not a valid token", next))
+			let kind = SyntaxKind::MultipleUnknowns(first.clone(), name);
+			Err(ComputorError::syntax(kind, Some(span)))
+		}
+		(None, _) =>
+		{
+			unknowns.names.push(name);
+			Ok((span, unknowns.names.len() as u32))
 		}
 	}
+}
+
+// Read a variable with its exponent, start is the position of its first letter c
+// In a system the variables must be of degree 1 and the degree of the token is the index of the unknown
+// ex: "2x + 3y" => Var(2, 1), Operator(Add), Var(3, 2)
+fn get_variable<N: Scalar, T: Iterator<Item=(usize, char)>>(start: usize, c: char, chars: &mut Peekable<T>, token_list: &HashSet<char>, unknowns: &mut Unknowns) -> Result<SpannedToken<N>, ComputorError>
+{
+	let (name, index) = get_unknown(start, c, chars, unknowns)?;
+	match (get_var_exponent(name, chars, token_list)?, unknowns.system)
+	{
+		((Token::Var((n, 1)), span), true) => Ok((Token::Var((n, index)), span)),
+		((_, span), true) => Err(ComputorError::syntax(SyntaxKind::NotLinear, Some(span))),
+		(token, false) => Ok(token)
+	}
+}
+
+// name is the span of the variable name
+pub fn get_var_exponent<N: Scalar, T: Iterator<Item=(usize, char)>>(name: Span, chars: &mut Peekable<T>, token_list: &HashSet<char>) -> Result<SpannedToken<N>, ComputorError>
+{
 	skip_spaces(chars);
-	while let Some(next) = chars.peek()
+	let caret = match chars.peek()
 	{
-		let token = match next
+		Some((pos, '²')) =>
 		{
-			'0' => Ok(Token::Var((1.0, 0))),
-			'1' => Ok(Token::Var((1.0, 1))),
-			'2' => Ok(Token::Var((1.0, 2))),
-			'3'...'9' => Err(format!("Syntax error: cannot handle a polynomial degree higher than 2")),
-			_ if token_list.contains(next) => Ok(Token::Var((1.0, 1))),
-			_ => Err(format!("Lexical error: '{}' is not a valid token", next))
-		};
-		chars.next();
-		return token;
-	}
-	match chars.peek()
+			let end = pos + 1;
+			chars.next();
+			return Ok((Token::Var((N::one(), 2)), Span::new(name.start, end)))
+		}
+		Some((pos, '^')) => *pos,
+		Some((_, next)) if is_valid(*next, token_list) => return Ok((Token::Var((N::one(), 1)), name)),
+		Some((pos, next)) => return Err(ComputorError::Lexical { ch: *next, pos: *pos }),
+		None => return Ok((Token::Var((N::one(), 1)), name))
+	};
+	chars.next();
+	let (degree, span) = get_exponent(caret, chars, token_list)?;
+	check_degree(degree, Some(span))?;
+	Ok((Token::Var((N::one(), degree)), Span::new(name.start, span.end)))
+}
+
+fn check_degree(degree: u32, span: Option<Span>) -> Result<(), ComputorError>
+{
+	if degree > MAX_DEGREE
 	{
-		None => Ok(Token::Var((1.0, 1))),
-		_ => Err(format!("Syntax error: expected a valid exponent after '^'"))
+		return Err(ComputorError::DegreeTooHigh { max: MAX_DEGREE, span });
 	}
+	Ok(())
 }
 
-pub fn get_comparison<T: Iterator<Item=char>>(c: char, chars: &mut Peekable<T>, token_list: &HashSet<char>) -> Result<Token, String>
+pub fn get_comparison<N: Scalar, T: Iterator<Item=(usize, char)>>(start: usize, c: char, chars: &mut Peekable<T>, token_list: &HashSet<char>) -> Result<SpannedToken<N>, ComputorError>
 {
-	if let Some(next) = chars.peek()
+	if let Some((pos, next)) = chars.peek()
 	{
 		if c == '=' && *next != '>' && *next != '<' && *next != '='
 		{
-			return Ok(Token::Cmp(Comparison::Equal));
+			return Ok((Token::Cmp(Comparison::Equal), Span::at(start)));
 		}
 		else if (c == '>' || c == '<') && *next != '>' && *next != '<'
 		{
-			if token_list.contains(next) == false
+			if !is_valid(*next, token_list)
 			{
-				return Err(format!("Lexical error: '{}' is not a valid token", next));
+				return Err(ComputorError::Lexical { ch: *next, pos: *pos });
 			}
 			if *next == '='
 			{
+				let span = Span::new(start, pos + 1);
 				chars.next();
-				return Ok(if c == '>' { Token::Cmp(Comparison::GreaterEq) } else { Token::Cmp(Comparison::LowerEq) });
+				return Ok((if c == '>' { Token::Cmp(Comparison::GreaterEq) } else { Token::Cmp(Comparison::LowerEq) }, span));
 			}
-			return Ok(if c == '>' { Token::Cmp(Comparison::Greater) } else { Token::Cmp(Comparison::Lower) });
+			return Ok((if c == '>' { Token::Cmp(Comparison::Greater) } else { Token::Cmp(Comparison::Lower) }, Span::at(start)));
 		}
 		else
 		{
-			return Err(format!("Syntax error: '{}' is invalid after '{}'", c, next));
+			let kind = SyntaxKind::UnexpectedToken { found: next.to_string(), after: c.to_string() };
+			return Err(ComputorError::syntax(kind, Some(Span::at(*pos))));
 		}
 	}
-	Err(format!("Syntax error: '{}' cannot be at the end of the expression", c))
+	Err(ComputorError::syntax(SyntaxKind::UnexpectedEnd(c.to_string()), Some(Span::at(start))))
+}
+
+// Check if a '-' read after the last stored token is an unary operator
+fn is_unary_position<N: Scalar>(tokens: &[SpannedToken<N>]) -> bool
+{
+	matches!(tokens.last(), None | Some((Token::Operator(_), _)) | Some((Token::Cmp(_), _)) | Some((Token::Open, _)))
 }
 
 // Returns a list of valid tokens
 pub fn get_token_list() -> HashSet<char>
 {
-	let mut list: HashSet<char> = ['+', '-', '/', '*', '^', '²', '>', '<', '=', 'x', 'X', ' ', '\t']
+	let mut list: HashSet<char> = ['+', '-', '/', '*', '^', '²', '>', '<', '=', '(', ')', ' ', '\t']
 		.iter().cloned().collect();
 
 	// Add all digits in the list of valid tokens
@@ -166,23 +260,17 @@ pub fn get_token_list() -> HashSet<char>
 	list
 }
 
-pub fn get_tokens(expression: &String) -> Result<(Vec<Token>, Comparison), String>
+// Read the tokens of an equation until the end of the expression, or until a ';' in a system
+fn get_equation_tokens<N: Scalar, T: Iterator<Item=(usize, char)>>(chars: &mut Peekable<T>, token_list: &HashSet<char>, unknowns: &mut Unknowns) -> Result<(Vec<SpannedToken<N>>, Comparison), ComputorError>
 {
-	// A list of valid tokens
-	let token_list = get_token_list();
-	
 	// A variable holding the type of comparison if there is one
 	let mut cmp_token = Comparison::No;
-	
-	// An iterator on every chars of the expression
-	// 'peekable' means that the iterator can check the next char
-	let mut chars = expression.chars().peekable();
 
-	// The list of tokens to return
-	let mut tokens: Vec<Token> = vec![];
+	// The list of tokens to return with the characters they were read from
+	let mut tokens: Vec<SpannedToken<N>> = vec![];
 
 	// Loop through every char of the expression
-	while let Some(c) = chars.next()
+	while let Some((pos, c)) = chars.next()
 	{
 		match c
 		{
@@ -190,210 +278,460 @@ pub fn get_tokens(expression: &String) -> Result<(Vec<Token>, Comparison), Strin
 			' ' | '\t' => (),
 
 			// Get the coefficient with its power. ex: 4.5x^2 => Token::Var(4.5, 2)
-			'0'...'9' => tokens.push(get_number(c, &mut chars, &token_list)?),
+			'0'..='9' => tokens.push(get_number(pos, c, chars, token_list, unknowns)?),
 
 			// Same as above but with coef 1
-			'x' | 'X' => tokens.push(get_var_exponent(&mut chars, &token_list)?),
+			_ if is_letter(c) => tokens.push(get_variable(pos, c, chars, token_list, unknowns)?),
 
-			'^' | '²' => return Err(format!("Syntax error: '^' is only valid after a number or a variable")),
-			'+' => tokens.push(Token::Operator(Operator::Add)),
+			// The exponent of a parenthesized expression. ex: (x + 1)^2
+			'^' | '²' if matches!(tokens.last(), Some((Token::Close, _))) =>
+			{
+				let (power, span) = match c
+				{
+					'^' => get_exponent(pos, chars, token_list)?,
+					_ => (2, Span::at(pos))
+				};
+				tokens.push((Token::Power(power), Span::new(pos, span.end)));
+			}
+			'^' | '²' => return Err(ComputorError::syntax(SyntaxKind::MisplacedExponent, Some(Span::at(pos)))),
+			'+' => tokens.push((Token::Operator(Operator::Add), Span::at(pos))),
 			'-' =>
 			{
-				skip_spaces(&mut chars);
+				skip_spaces(chars);
 				// Check the next token to see if the program should treat it as an unary operator or not
-				if let Some(next) = chars.peek()
+				if let Some((_, next)) = chars.peek()
 				{
 					match next
 					{
 						// Treat '-' as an unary operator and change the sign of the next number
-						'0'...'9' =>
+						'0'..='9' =>
 						{
-							let num = chars.next().unwrap();
-							let token = get_number(num, &mut chars, &token_list)?;
-							if let Token::Var((n, p)) = token
+							let (start, num) = chars.next().unwrap();
+							let token: SpannedToken<N> = get_number(start, num, chars, token_list, unknowns)?;
+							if let (Token::Var((n, p)), span) = token
 							{
 								// Check the last stored token to see if the program need to add a '+'
-								match tokens.last()
+								if !is_unary_position(&tokens)
 								{
-									Some(Token::Operator(_)) | Some(Token::Cmp(_))=> (),
-									_ if tokens.len() == 0 => (),
-									// If the program is not a operator / cmp and if not the first token,
+									// If the last token is not an operator / cmp / '(' and if not the first token,
 									// then add a '+' before it
-									_ => tokens.push(Token::Operator(Operator::Add))
+									tokens.push((Token::Operator(Operator::Add), Span::at(pos)))
 								}
-								// mu
-								tokens.push(Token::Var((n * -1.0, p)));
+								tokens.push((Token::Var((-n, p)), Span::new(pos, span.end)));
 							}
 						}
-						_ => tokens.push(Token::Operator(Operator::Sub))
+						// Before a variable or a group, the parser reads a '-' in an unary position as a negation
+						// ex: "2 / -(x + 1)" => 2 / (-(x + 1))
+						_ => tokens.push((Token::Operator(Operator::Sub), Span::at(pos)))
 					};
 					continue
 				}
-				return Err(format!("Syntax error: '-' is invalid at the end"));
+				return Err(ComputorError::syntax(SyntaxKind::UnexpectedEnd(c.to_string()), Some(Span::at(pos))));
 			}
-			'*' => tokens.push(Token::Operator(Operator::Mult)),
-			'/' => tokens.push(Token::Operator(Operator::Div)),
+			'*' => tokens.push((Token::Operator(Operator::Mult), Span::at(pos))),
+			'/' => tokens.push((Token::Operator(Operator::Div), Span::at(pos))),
+			'(' => tokens.push((Token::Open, Span::at(pos))),
+			')' => tokens.push((Token::Close, Span::at(pos))),
 			'>' | '<' | '=' =>
 			{
 				// If there is already a comparison token
 				if cmp_token != Comparison::No
 				{
-					return Err(format!("Syntax error: '{}' expression cannot have more than one comparison", c))
+					return Err(ComputorError::syntax(SyntaxKind::MultipleComparisons, Some(Span::at(pos))))
 				}
-				if tokens.len() == 0
+				if tokens.is_empty()
 				{
-					return Err(format!("Syntax error: '{}' cannot be at the beginning of the expression", c))
+					return Err(ComputorError::syntax(SyntaxKind::UnexpectedStart(c.to_string()), Some(Span::at(pos))))
 				}
-				let token = get_comparison(c, &mut chars, &token_list)?;
+				let token = get_comparison(pos, c, chars, token_list)?;
 
 				// Unwrap the comparison token and store it
-				if let Token::Cmp(cmp) = &token { cmp_token = cmp.clone() }
+				if let (Token::Cmp(cmp), _) = &token { cmp_token = cmp.clone() }
 
 				tokens.push(token);
 			}
-			_ => return Err(format!("Lexical error: '{}' is not a valid token", c))
+			';' if unknowns.system => break,
+			_ => return Err(ComputorError::Lexical { ch: c, pos })
 		}
 	}
 	Ok((tokens, cmp_token))
 }
 
-// Reduce the coefficients of the expression
-pub fn reduce_tokens(tokens: Vec<Token>) -> Result<Vec<Token>, String>
+// Returns the tokens, the comparison and the name of the unknown, "x" when there is no variable
+pub fn get_tokens<N: Scalar>(expression: &str) -> Result<(Vec<SpannedToken<N>>, Comparison, String), ComputorError>
 {
-	let mut list: Vec<Token> = vec![];
-	for current in tokens
-	{
-		let last = list.pop();
+	// An iterator on every chars of the expression with their position
+	// 'peekable' means that the iterator can check the next char
+	let mut chars = expression.chars().enumerate().peekable();
+	let mut unknowns = Unknowns { names: vec![], system: false };
+	let (tokens, cmp_token) = get_equation_tokens(&mut chars, &get_token_list(), &mut unknowns)?;
+	let name = unknowns.names.into_iter().next().unwrap_or_else(|| "x".to_string());
+	Ok((tokens, cmp_token, name))
+}
 
-		// Check if there is already a token stored
-		match &last
+// Returns the tokens of each equation of a system separated by ';' and the names of its unknowns
+// A variable token has the index of its unknown from 1 as degree, a constant has the degree 0
+// ex: "2x + 3y = 5; x - y = 1" => [[Var(2, 1), Operator(Add), Var(3, 2), Cmp(Equal), Var(5, 0)], [...]], ["x", "y"]
+pub fn get_system_tokens<N: Scalar>(expression: &str) -> Result<(SystemTokens<N>, Vec<String>), ComputorError>
+{
+	let mut token_list = get_token_list();
+	token_list.insert(';');
+	let mut chars = expression.chars().enumerate().peekable();
+	let mut unknowns = Unknowns { names: vec![], system: true };
+	let mut equations: Vec<Vec<SpannedToken<N>>> = vec![];
+	while chars.peek().is_some()
+	{
+		let (tokens, cmp_token) = get_equation_tokens(&mut chars, &token_list, &mut unknowns)?;
+		// Empty equations are skipped. ex: "x = 1;"
+		let span = match (tokens.first(), tokens.last())
 		{
-			Some(token) =>
+			(Some((_, first)), Some((_, last))) => Span::new(first.start, last.end),
+			_ => continue
+		};
+		match cmp_token
+		{
+			Comparison::Equal => equations.push(tokens),
+			Comparison::No => return Err(ComputorError::syntax(SyntaxKind::ExpectedEqual, Some(span))),
+			cmp =>
 			{
-				// There is no valid case with two consecutive tokens of the same type
-				// ex: "2 + + 3" | "2 + 3x 5x^2"
-				// note that "2 x" is valid
-				if token.same_type_as(&current)
-				{
-					return Err(format!("Syntax error: '{}' is not a valid token after '{}'",
-						current, token));
-				}
+				let (_, cmp_span) = tokens.iter().find(|(token, _)| matches!(token, Token::Cmp(_))).unwrap();
+				return Err(ComputorError::syntax(SyntaxKind::SystemComparison(cmp.to_string()), Some(*cmp_span)));
 			}
+		}
+	}
+	if equations.is_empty()
+	{
+		return Err(ComputorError::syntax(SyntaxKind::EmptyExpression, None));
+	}
+	Ok((equations, unknowns.names))
+}
+
+// The tokens of an equation being parsed into an expression tree
+struct Parser<'a, N>
+{
+	tokens: &'a [SpannedToken<N>],
+	pos: usize,
+
+	// The names of the unknowns, only used to write the errors
+	names: &'a [String],
+
+	// In a system the degree of a variable token is the index of its unknown
+	system: bool
+}
+
+// How tightly an infix operator binds its operands, a juxtaposition is a product
+// ex: 1 + 2 * 3 => 1 + (2 * 3)
+fn binding_power(op: &Operator) -> u8
+{
+	match op
+	{
+		Operator::Add | Operator::Sub => 1,
+		Operator::Mult | Operator::Div => 2
+	}
+}
+
+// A '-' before an operand applies to it only. ex: -x / 2 => (-x) / 2
+const NEGATION: u8 = 3;
+
+impl<'a, N: Scalar> Parser<'a, N>
+{
+	// A token written with the name of its unknown. ex: Var(3, 2) with ["x", "y"] in a system => 3y
+	fn token_name(&self, token: &Token<N>) -> String
+	{
+		match token
+		{
+			Token::Var((n, i)) if self.system && *i > 0 => Token::Var((*n, 1)).display_with(&self.names[*i as usize - 1]),
+			_ => token.display_with(self.names.first().map_or("x", |name| name.as_str()))
+		}
+	}
+
+	fn error(&self, kind: SyntaxKind, span: Span) -> ComputorError
+	{
+		ComputorError::syntax(kind, Some(span))
+	}
+
+	// The error for the token at pos that cannot be there
+	fn unexpected(&self, pos: usize) -> ComputorError
+	{
+		let (token, span) = &self.tokens[pos];
+		let kind = match pos
+		{
+			0 => SyntaxKind::ExpectedOperand(self.token_name(token)),
+			_ => SyntaxKind::UnexpectedToken { found: self.token_name(token), after: self.token_name(&self.tokens[pos - 1].0) }
+		};
+		self.error(kind, *span)
+	}
+
+	// The expression of a variable token
+	// ex: Var(4.5, 2) => Mul(Num(4.5), Pow(Var(0), 2))
+	fn variable(&self, n: N, d: u32, span: Span) -> SpannedExpr<N>
+	{
+		let var = match (d, self.system)
+		{
+			(0, _) => return (Expr::Num(n), span),
+			(_, true) => Expr::Var(d as usize - 1),
+			(1, false) => Expr::Var(0),
+			(_, false) => Expr::Pow(Box::new((Expr::Var(0), span)), d)
+		};
+		match n == N::one()
+		{
+			true => (var, span),
+			false => (Expr::Mul(Box::new((Expr::Num(n), span)), Box::new((var, span))), span)
+		}
+	}
+
+	// A number, a variable, a negation or a parenthesized expression with its exponent
+	fn operand(&mut self) -> Result<SpannedExpr<N>, ComputorError>
+	{
+		let (token, span) = match self.tokens.get(self.pos)
+		{
+			Some((token, span)) => (token, *span),
+			// ex: "2 +"
 			None =>
 			{
-				// The first token of the expression should be a variable / coef
-				if current.is_variable() == false
-				{
-					return Err(format!("Syntax error: expected a variable or number instead of '{}'",
-						current));
-				}
-				list.push(current);
-				continue
+				let (last, span) = self.tokens.last().unwrap();
+				return Err(self.error(SyntaxKind::ExpectedOperand(self.token_name(last)), *span));
 			}
-		}
-
-		match (&last, &current)
+		};
+		self.pos += 1;
+		let mut operand = match token
 		{
-			// ex: '2' 'X^2' => 2 * X^2
-			(Some(Token::Var((n1, d1))), Token::Var((n2, d2))) =>
+			Token::Var((n, d)) => self.variable(*n, *d, span),
+			Token::Operator(Operator::Sub) =>
 			{
-				// Can't multiply something like '2x^1' '3x^2'
-				if *d1 != 0
-				{
-					return Err(format!("Syntax error: '{}' is not a valid token after '{}'",
-						current, last.unwrap()));
-				}
-				// Multiply the two numbers
-				list.push(Token::Var((do_op(*n1, *n2, &Operator::Mult)?, *d2)));
+				let operand = self.expression(NEGATION)?;
+				let negation = Span::new(span.start, operand.1.end);
+				(Expr::Neg(Box::new(operand)), negation)
 			}
-			(Some(Token::Operator(Operator::Mult)), Token::Var((n2, d2))) =>
+			Token::Open =>
 			{
-				let prev = list.pop().unwrap();
-				if let Token::Var((n1, d1)) = prev
+				if let Some((Token::Close, close)) = self.tokens.get(self.pos)
 				{
-					// '*' operator can only multiply two numbers with at least one of degree 0
-					if d1 > 0 && *d2 > 0
-					{
-						return Err(format!("Syntax error: can't multiply '{}' with '{}'",
-							prev, current));
-					}
-					list.push(Token::Var((do_op(n1, *n2, &Operator::Mult)?, d1 + d2)));
+					return Err(self.error(SyntaxKind::EmptyGroup, Span::new(span.start, close.end)));
 				}
-			}
-			(Some(Token::Operator(Operator::Div)), Token::Var((n2, d2))) =>
-			{
-				let prev = list.pop().unwrap();
-				if let Token::Var((n1, d1)) = prev
+				let (inner, _) = self.expression(1)?;
+				match self.tokens.get(self.pos)
 				{
-					// the denominator cannot be a variable (coef with power greater than 0)
-					if *d2 > 0
+					Some((Token::Close, close)) =>
+					{
+						self.pos += 1;
+						(inner, Span::new(span.start, close.end))
+					}
+					// ex: "(x = 1) + 2"
+					Some((Token::Cmp(cmp), cmp_span)) if self.tokens[self.pos..].iter().any(|(token, _)| *token == Token::Close) =>
 					{
-						return Err(format!("Syntax error: can't divide '{}' with '{}'",
-							prev, current));
+						return Err(self.error(SyntaxKind::ComparisonInGroup(cmp.to_string()), *cmp_span))
 					}
-					list.push(Token::Var((do_op(n1, *n2, &Operator::Div)?, d1)));
+					_ => return Err(self.error(SyntaxKind::UnmatchedOpen, span))
 				}
 			}
-			(Some(Token::Cmp(_)), _) =>
+			Token::Close => return Err(self.error(SyntaxKind::UnmatchedClose, span)),
+			Token::Cmp(cmp) => return Err(self.error(SyntaxKind::ComparisonOperands(cmp.to_string()), span)),
+			_ => return Err(self.unexpected(self.pos - 1))
+		};
+		// ex: (x + 1)²
+		while let Some((Token::Power(n), power)) = self.tokens.get(self.pos)
+		{
+			self.pos += 1;
+			let span = Span::new(operand.1.start, power.end);
+			operand = (Expr::Pow(Box::new(operand), *n), span);
+		}
+		Ok(operand)
+	}
+
+	// Precedence climbing: read the operands joined by the operators binding at least as tight as min
+	// ex: 2 * 3 + 4 / 2x => Add(Mul(2, 3), Div(4, 2x))
+	fn expression(&mut self, min: u8) -> Result<SpannedExpr<N>, ComputorError>
+	{
+		let mut left = self.operand()?;
+		loop
+		{
+			let op = match self.tokens.get(self.pos)
 			{
-				let prev = list.pop().unwrap();
-				if prev.is_variable() && current.is_variable()
+				Some((Token::Operator(op), _)) => op.clone(),
+				// "2 x" is valid but "x 2" is not, a product with a variable on the left needs a '*'
+				Some((Token::Var(_), _)) => match &self.tokens[self.pos - 1].0
 				{
-					list.push(prev);
-					list.push(last.unwrap());
-					list.push(current);
-				}
-				else
+					Token::Var((_, 0)) | Token::Close | Token::Power(_) => Operator::Mult,
+					_ => return Err(self.unexpected(self.pos))
+				},
+				// ex: 2(x + 1) | (x + 1)(x - 1)
+				Some((Token::Open, _)) => Operator::Mult,
+				_ => break
+			};
+			let power = binding_power(&op);
+			if power < min { break }
+			if let Some((Token::Operator(_), _)) = self.tokens.get(self.pos) { self.pos += 1 }
+			let right = self.expression(power + 1)?;
+			let span = Span::new(left.1.start, right.1.end);
+			left = match op
+			{
+				Operator::Add => (Expr::Add(Box::new(left), Box::new(right)), span),
+				Operator::Sub =>
 				{
-					return Err(format!("Syntax error: '{}' need to be between two numbers or variables",
-						last.unwrap()));
+					let negation = (Expr::Neg(Box::new(right.clone())), right.1);
+					(Expr::Add(Box::new(left), Box::new(negation)), span)
 				}
-			}
-			_ =>
-			{
-				list.push(last.unwrap());
-				list.push(current);
-			}
+				Operator::Mult => (Expr::Mul(Box::new(left), Box::new(right)), span),
+				Operator::Div => (Expr::Div(Box::new(left), Box::new(right)), span)
+			};
 		}
+		Ok(left)
 	}
-	// If the last token of the expression is a coef, then return the list of coefs
-	if let Some(Token::Var(_)) = list.last()
+}
+
+// Parse the tokens of an expression into the trees of the two sides of its comparison,
+// the right side is None when there is no comparison
+// names are the unknowns of the tokens, system is true for the tokens of get_system_tokens
+pub fn parse_tokens<N: Scalar>(tokens: &[SpannedToken<N>], names: &[String], system: bool) -> Result<(SpannedExpr<N>, Option<SpannedExpr<N>>), ComputorError>
+{
+	if tokens.is_empty()
 	{
-		Ok(list)
+		return Err(ComputorError::syntax(SyntaxKind::EmptyExpression, None));
 	}
-	else
+	let mut parser = Parser { tokens, pos: 0, names, system };
+	let left = parser.expression(1)?;
+	let right = match tokens.get(parser.pos)
 	{
-		return Err(format!("Syntax error: expected a variable or number instead of '{}'",
-			list.pop().unwrap()));
+		Some((Token::Cmp(_), _)) =>
+		{
+			parser.pos += 1;
+			Some(parser.expression(1)?)
+		}
+		_ => None
+	};
+	match tokens.get(parser.pos)
+	{
+		None => Ok((left, right)),
+		Some((Token::Close, span)) => Err(ComputorError::syntax(SyntaxKind::UnmatchedClose, Some(*span))),
+		Some(_) => Err(parser.unexpected(parser.pos))
 	}
 }
 
-// Get all the coefs on the same side of the equation and reduce them
-pub fn get_coefficients(tokens: &Vec<Token>) -> Vec<f64>
+// Get all the coefs on the same side of the equation and reduce them, name is the unknown
+// The expansion of each side and each term moved to the left side are recorded in the log
+// ex: 2 + 2x = 3x^2 => -3x^2 + 2x + 2
+pub fn get_coefficients<N: Scalar>(tokens: &[SpannedToken<N>], name: &str, log: &mut StepLog) -> Result<Polynomial<N>, ComputorError>
 {
-	let mut coef: Vec<f64> = vec![0.0, 0.0, 0.0];
-	let mut sign = 1.0;
-	let mut op = Operator::Add;
-	for token in tokens
+	let names = [name.to_string()];
+	let (left, right) = parse_tokens(tokens, &names, false)?;
+	let cmp = tokens.iter().find_map(|(token, _)| match token
 	{
-		match token
+		Token::Cmp(cmp) => Some(cmp.clone()),
+		_ => None
+	});
+	match (&right, &cmp)
+	{
+		(Some(right), Some(cmp)) => log.record(Verbosity::Explain, format!("Read as: {} {} {}", left.0.write(&names), cmp, right.0.write(&names))),
+		_ => log.record(Verbosity::Explain, format!("Read as: {}", left.0.write(&names)))
+	}
+	let mut poly = to_polynomial(&left, &names, log)?;
+	let (mut right, cmp) = match (right, cmp)
+	{
+		(Some(right), Some(cmp)) => (to_polynomial(&right, &names, log)?, cmp),
+		_ => return Ok(poly)
+	};
+	log.record(Verbosity::Explain, format!("Expanded: {} {} {}", poly.short_with(name), cmp, right.short_with(name)));
+
+	// ex: Subtract 4 from both sides: x² - 4 = 0
+	for (n, d) in right.terms()
+	{
+		let term = Polynomial::term(n, d);
+		poly = poly - term.clone();
+		right = right - term;
+		let step = match n < N::zero()
 		{
-			Token::Var((n, d)) =>
-			{
-				let degree = *d as usize;
-				coef[degree] = do_op(coef[degree], n * sign, &op).unwrap();
-			}
-			Token::Operator(Operator::Add) => op = Operator::Add,
-			Token::Operator(Operator::Sub) => op = Operator::Sub,
+			true => format!("Add {} to both sides", Token::Var((-n, d)).display_with(name)),
+			false => format!("Subtract {} from both sides", Token::Var((n, d)).display_with(name))
+		};
+		log.record(Verbosity::Explain, format!("{}: {} {} {}", step, poly.short_with(name), cmp, right.short_with(name)));
+	}
+	Ok(poly)
+}
 
-			// Change the sign of coef on the other side of the equation
-			Token::Cmp(_) => sign = -1.0,
-			_ => ()
-		}
+// Get the coefficient of each unknown of an equation read by get_system_tokens,
+// the unknowns are moved on the left side and the constants on the right side
+// ex: 2x + 1 = y - 3 => { x: 2, y: -1 } = -4
+pub fn get_linear_coefficients<N: Scalar>(tokens: &[SpannedToken<N>], names: &[String]) -> Result<LinearEquation<N>, ComputorError>
+{
+	let (left, right) = parse_tokens(tokens, names, true)?;
+	let mut form = to_linear(&left, names)?;
+	if let Some(right) = right
+	{
+		form = form - to_linear(&right, names)?;
+	}
+	let coefficients: BTreeMap<String, N> = names.iter().enumerate()
+		.map(|(i, name)| (name.clone(), form.coefficient(i as u32 + 1)))
+		.collect();
+	Ok(LinearEquation { coefficients, constant: -form.coefficient(0) })
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+
+	// The coefficients of the reduced form of the equation, lowest degree first
+	fn coefficients(expression: &str) -> Result<Vec<f64>, ComputorError>
+	{
+		let (tokens, _, variable) = get_tokens::<f64>(expression)?;
+		let poly = get_coefficients(&tokens, &variable, &mut StepLog::new(Verbosity::Quiet))?;
+		Ok(poly.coefficients().to_vec())
+	}
+
+	#[test]
+	fn precedence()
+	{
+		assert_eq!(coefficients("1 + 2 * 3 = x").unwrap(), vec![7.0, -1.0]);
+		assert_eq!(coefficients("2 * 3 + 4 / 2 = x").unwrap(), vec![8.0, -1.0]);
+		assert_eq!(coefficients("(1 + 2) * 3 = x").unwrap(), vec![9.0, -1.0]);
+	}
+
+	#[test]
+	fn chained_division()
+	{
+		// 8 / 4 / 2 = (8 / 4) / 2
+		assert_eq!(coefficients("8 / 4 / 2 = x").unwrap(), vec![1.0, -1.0]);
+		assert_eq!(coefficients("x / 2 / 2 = 1").unwrap(), vec![-1.0, 0.25]);
+	}
+
+	#[test]
+	fn implicit_multiplication()
+	{
+		assert_eq!(coefficients("2(x + 1) = 0").unwrap(), vec![2.0, 2.0]);
+		assert_eq!(coefficients("(x + 1)(x - 1) = 0").unwrap(), vec![-1.0, 0.0, 1.0]);
+		// 4 / 2x is a division by 2x, not (4 / 2)x
+		assert!(matches!(coefficients("2 * 3 + 4 / 2 x = 0"),
+			Err(ComputorError::Syntax { kind: SyntaxKind::InvalidDivision(..), .. })));
+	}
+
+	#[test]
+	fn unary_minus()
+	{
+		assert_eq!(coefficients("-x = 2").unwrap(), vec![-2.0, -1.0]);
+		assert_eq!(coefficients("x - -x = 2").unwrap(), vec![-2.0, 2.0]);
+		assert_eq!(coefficients("-x^2 = 4").unwrap(), vec![-4.0, 0.0, -1.0]);
+		assert_eq!(coefficients("2 * -(x + 1) = 0").unwrap(), vec![-2.0, -2.0]);
+		// The minus only applies to the next operand: -(x + 1)(x - 1) = -((x + 1)(x - 1))
+		assert_eq!(coefficients("-(x + 1)(x - 1) = 0").unwrap(), vec![1.0, 0.0, -1.0]);
+		// 2 / -x is a division by -x, not (2 / -1)x
+		assert!(matches!(coefficients("2 / -x = 1"), Err(ComputorError::Syntax { kind: SyntaxKind::InvalidDivision(..), .. })));
+		assert!(matches!(coefficients("6 / -(x + 1) = 1"), Err(ComputorError::Syntax { kind: SyntaxKind::InvalidDivision(..), .. })));
+		assert_eq!(coefficients("6 / -(1 + 2) = x").unwrap(), vec![-2.0, -1.0]);
 	}
-	// ex: 2 + 2x - 3x^2 => - 3x^2 + 2x + 2
-	coef.reverse();
 
-	// Return the list of coefs
-	coef
-}
\ No newline at end of file
+	#[test]
+	fn read_as()
+	{
+		let read_as = |expression: &str|
+		{
+			let (tokens, _, variable) = get_tokens::<f64>(expression).unwrap();
+			let mut log = StepLog::new(Verbosity::Explain);
+			get_coefficients(&tokens, &variable, &mut log).unwrap();
+			log.lines()[0].clone()
+		};
+		assert_eq!(read_as("2(x+1)^2 = x - 3"), "Read as: 2(x + 1)² = x - 3");
+		assert_eq!(read_as("x - 3x^2 = -x"), "Read as: x - 3x² = -x");
+		assert_eq!(read_as("-3 + x = 2 / -4"), "Read as: -3 + x = 2 / -4");
+	}
+}
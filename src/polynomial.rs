@@ -0,0 +1,261 @@
+use std::fmt;
+use std::ops::{Add, Sub, Mul};
+
+use crate::{Latex, Token, Scalar};
+use crate::ComputorError;
+
+// The highest degree accepted by the parser
+pub const MAX_DEGREE: u32 = 100;
+
+// A polynomial stored as a dense list of coefficients where the index is the degree
+// ex:	3x² - 2x + 1 => Polynomial { coef: [1.0, -2.0, 3.0] }
+// The list never ends with a null coefficient so the last index is the degree
+// The coefficients are f64, or Rational in exact mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<T = f64>
+{
+	coef: Vec<T>
+}
+
+impl<T: Scalar> Default for Polynomial<T>
+{
+	fn default() -> Self
+	{
+		Polynomial::new()
+	}
+}
+
+impl<T: Scalar> Polynomial<T>
+{
+	// The null polynomial
+	pub fn new() -> Self
+	{
+		Polynomial { coef: vec![] }
+	}
+
+	// Build a polynomial from its coefficients sorted by increasing degree
+	pub fn from_coefficients(coef: Vec<T>) -> Self
+	{
+		let mut poly = Polynomial { coef };
+		poly.trim();
+		poly
+	}
+
+	// A single term polynomial. ex: term(4.0, 3) => 4x³
+	pub fn term(n: T, degree: u32) -> Self
+	{
+		let mut poly = Polynomial::new();
+		poly.add_term(n, degree);
+		poly
+	}
+
+	// Remove the null coefficients of highest degree
+	fn trim(&mut self)
+	{
+		while let Some(n) = self.coef.last()
+		{
+			if *n != T::zero() { break }
+			self.coef.pop();
+		}
+	}
+
+	pub fn add_term(&mut self, n: T, degree: u32)
+	{
+		let degree = degree as usize;
+		if self.coef.len() <= degree
+		{
+			self.coef.resize(degree + 1, T::zero());
+		}
+		self.coef[degree] = self.coef[degree] + n;
+		self.trim();
+	}
+
+	pub fn degree(&self) -> u32
+	{
+		match self.coef.len()
+		{
+			0 => 0,
+			len => len as u32 - 1
+		}
+	}
+
+	// Returns the coefficient of the given degree, 0 if there is no such term
+	pub fn coefficient(&self, degree: u32) -> T
+	{
+		match self.coef.get(degree as usize)
+		{
+			Some(n) => *n,
+			None => T::zero()
+		}
+	}
+
+	// The coefficients sorted by increasing degree
+	pub fn coefficients(&self) -> &[T]
+	{
+		&self.coef
+	}
+
+	pub fn is_zero(&self) -> bool
+	{
+		self.coef.is_empty()
+	}
+
+	pub fn is_constant(&self) -> bool
+	{
+		self.coef.len() <= 1
+	}
+
+	// Returns the non null terms as (coefficient, degree) sorted by increasing degree
+	pub fn terms(&self) -> Vec<(T, u32)>
+	{
+		self.coef.iter().enumerate()
+			.filter(|(_, n)| **n != T::zero())
+			.map(|(d, n)| (*n, d as u32))
+			.collect()
+	}
+
+	pub fn is_finite(&self) -> bool
+	{
+		self.coef.iter().all(|n| n.is_finite())
+	}
+
+	pub fn to_f64(&self) -> Polynomial
+	{
+		Polynomial::from_coefficients(self.coef.iter().map(|n| n.to_f64()).collect())
+	}
+
+	pub fn scale(&self, n: T) -> Polynomial<T>
+	{
+		Polynomial::from_coefficients(self.coef.iter().map(|c| *c * n).collect())
+	}
+
+	// A division by zero reports the whole polynomial as the dividend, written with x as the unknown
+	pub fn divide(&self, n: T) -> Result<Polynomial<T>, ComputorError>
+	{
+		if n == T::zero()
+		{
+			return Err(ComputorError::DivisionByZero { dividend: self.short_with("x"), span: None });
+		}
+		Ok(Polynomial::from_coefficients(self.coef.iter().map(|c| *c / n).collect()))
+	}
+}
+
+impl Polynomial
+{
+	// ex: 3x² - 2x + 1 => 6x - 2
+	pub fn derivative(&self) -> Polynomial
+	{
+		Polynomial::from_coefficients(self.coef.iter().enumerate().skip(1).map(|(d, n)| n * d as f64).collect())
+	}
+}
+
+impl<T: Scalar> Add for Polynomial<T>
+{
+	type Output = Polynomial<T>;
+
+	fn add(self, other: Polynomial<T>) -> Polynomial<T>
+	{
+		let mut sum = self;
+		for (n, d) in other.terms()
+		{
+			sum.add_term(n, d);
+		}
+		sum
+	}
+}
+
+impl<T: Scalar> Sub for Polynomial<T>
+{
+	type Output = Polynomial<T>;
+
+	fn sub(self, other: Polynomial<T>) -> Polynomial<T>
+	{
+		self + other.scale(-T::one())
+	}
+}
+
+impl<T: Scalar> Mul for Polynomial<T>
+{
+	type Output = Polynomial<T>;
+
+	// Multiply every term of self with every term of other
+	// ex: (x + 1)(x - 1) => x² + x - x - 1 => x² - 1
+	fn mul(self, other: Polynomial<T>) -> Polynomial<T>
+	{
+		if self.is_zero() || other.is_zero()
+		{
+			return Polynomial::new();
+		}
+		let mut product = vec![T::zero(); self.coef.len() + other.coef.len() - 1];
+		for (d1, n1) in self.coef.iter().enumerate()
+		{
+			for (d2, n2) in other.coef.iter().enumerate()
+			{
+				product[d1 + d2] = product[d1 + d2] + *n1 * *n2;
+			}
+		}
+		Polynomial::from_coefficients(product)
+	}
+}
+
+impl<T: Scalar> Polynomial<T>
+{
+	// The terms by decreasing degree written with term, the constant term is always written
+	fn write_terms(&self, term: impl Fn(&Token<T>) -> String) -> String
+	{
+		let mut to_display = String::new();
+		for d in (0..=self.degree()).rev()
+		{
+			let n = self.coefficient(d);
+			if n == T::zero() && d != 0 { continue }
+			match (to_display.is_empty(), n >= T::zero())
+			{
+				(true, _) => to_display.push_str(&term(&Token::Var((n, d)))),
+				(false, true) => to_display.push_str(&format!(" + {}", term(&Token::Var((n, d))))),
+				(false, false) => to_display.push_str(&format!(" - {}", term(&Token::Var((-n, d)))))
+			}
+		}
+		to_display
+	}
+
+	// The polynomial written with name as the unknown. ex: t² - 4t + 0
+	pub fn display_with(&self, name: &str) -> String
+	{
+		self.write_terms(|token| token.display_with(name))
+	}
+
+	// Without the null constant term that the reduced form keeps. ex: t² - 4t
+	pub fn short_with(&self, name: &str) -> String
+	{
+		let to_display = self.display_with(name);
+		match to_display.strip_suffix(" + 0")
+		{
+			Some(short) => short.to_string(),
+			None => to_display
+		}
+	}
+
+	pub fn latex_with(&self, name: &str) -> String
+	{
+		self.write_terms(|token| token.latex_with(name))
+	}
+}
+
+impl<T: Scalar> fmt::Display for Polynomial<T>
+{
+	// Display the terms by decreasing degree, the constant term is always displayed
+	// ex: x³ - 2x + 0
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		write!(f, "{}", self.display_with("x"))
+	}
+}
+
+impl<T: Scalar> Latex for Polynomial<T>
+{
+	// ex: x^{3} - 2x + 0
+	fn latex(&self) -> String
+	{
+		self.latex_with("x")
+	}
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+
+use crate::rational::{gcd, Rational};
+
+// The two roots (p ± q√r) / d of a quadratic equation with rational coefficients
+// r is square-free and negative when the roots are complex, d is strictly positive
+// ex:	2x² - 3x - 1 = 0 => Surd { p: 3, q: 1, r: 17, d: 4 } => (3 ± √17) / 4
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Surd
+{
+	pub p: i128,
+	pub q: i128,
+	pub r: i128,
+	pub d: i128
+}
+
+impl Surd
+{
+	// The two roots sorted when there is no radical left, ie: the discriminant is a perfect square
+	pub fn rational_roots(&self) -> Option<(Rational, Rational)>
+	{
+		match self.r == 1 || self.q == 0
+		{
+			true => Some((Rational::new(self.p - self.q, self.d), Rational::new(self.p + self.q, self.d))),
+			false => None
+		}
+	}
+}
+
+// Split n >= 0 into k²m where m is square-free. ex: 72 => (6, 2)
+// Only the factors up to 10⁶ are looked for so m may keep a larger square factor
+pub fn square_free(n: i128) -> (i128, i128)
+{
+	let (mut k, mut m) = (1, n);
+	if n == 0 { return (0, 1) }
+	let mut i: i128 = 2;
+	while i * i <= m && i <= 1_000_000
+	{
+		while m % (i * i) == 0
+		{
+			k *= i;
+			m /= i * i;
+		}
+		i += 1;
+	}
+	(k, m)
+}
+
+// The exact roots of ax² + bx + c = 0 when the coefficients are fractions
+pub fn quadratic_surd(a: f64, b: f64, c: f64) -> Option<Surd>
+{
+	let (a, b, c) = (Rational::from_f64(a)?, Rational::from_f64(b)?, Rational::from_f64(c)?);
+
+	// Multiply the equation by the lcm of the denominators to only keep integers
+	let lcm = [a, b, c].iter().try_fold(1_i128, |lcm, n| (lcm / gcd(lcm, n.denominator())).checked_mul(n.denominator()))?;
+	let integer = |n: Rational| n.numerator().checked_mul(lcm / n.denominator());
+	let (a, b, c) = (integer(a)?, integer(b)?, integer(c)?);
+
+	let delta = b.checked_mul(b)?.checked_sub(a.checked_mul(c)?.checked_mul(4)?)?;
+	let (k, m) = square_free(delta.abs());
+	let r = if delta < 0 { -m } else { m };
+
+	// (-b ± k√r) / 2a reduced by the gcd of -b, k and 2a
+	let (p, q, d) = (-b, k, a.checked_mul(2)?);
+	let divisor = gcd(gcd(p, q), d);
+	let sign = if d < 0 { -1 } else { 1 };
+	Some(Surd { p: sign * p / divisor, q: q / divisor, r, d: sign * d / divisor })
+}
+
+impl fmt::Display for Surd
+{
+	// ex: (3 ± 2√5) / 4 | ±√2 / 2 | -1 ± 2i | (-1 ± i√3) / 2
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		let factor = if self.q == 1 { String::new() } else { self.q.to_string() };
+		let radical = match self.r
+		{
+			1 => self.q.to_string(),
+			-1 => format!("{}i", factor),
+			r if r < 0 => format!("{}i√{}", factor, -r),
+			r => format!("{}√{}", factor, r)
+		};
+		let to_display = match (self.p == 0, self.d == 1)
+		{
+			(true, true) => format!("±{}", radical),
+			(true, false) => format!("±{} / {}", radical, self.d),
+			(false, true) => format!("{} ± {}", self.p, radical),
+			(false, false) => format!("({} ± {}) / {}", self.p, radical, self.d)
+		};
+		write!(f, "{}", to_display)
+	}
+}
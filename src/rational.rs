@@ -0,0 +1,316 @@
+use std::fmt;
+use std::cmp::Ordering;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+// The largest denominator looked for when converting a f64 to a fraction
+const MAX_DENOMINATOR: i128 = 1_000_000_000_000;
+
+// An exact fraction num / den, always reduced with a strictly positive denominator
+// ex:	Rational::new(14, -6) => -7/3
+// A computation that does not fit in an i128 gives an overflowed rational (den = 0),
+// it behaves like an infinite f64 and is displayed as '∞'
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rational
+{
+	num: i128,
+	den: i128
+}
+
+// Greatest common divisor, always positive except for gcd(i128::MIN, 0) = 2^127 which gives i128::MIN
+pub fn gcd(a: i128, b: i128) -> i128
+{
+	let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+	while b != 0
+	{
+		let r = a % b;
+		a = b;
+		b = r;
+	}
+	a as i128
+}
+
+impl Rational
+{
+	// i128::MIN can't be negated, like a division by 0 it gives an overflowed rational
+	pub fn new(num: i128, den: i128) -> Self
+	{
+		if den == 0
+		{
+			return Rational::overflow(num >= 0);
+		}
+		if num == i128::MIN || den == i128::MIN
+		{
+			return Rational::overflow((num >= 0) == (den > 0));
+		}
+		let divisor = gcd(num, den);
+		let sign = if den < 0 { -1 } else { 1 };
+		Rational { num: sign * num / divisor, den: sign * den / divisor }
+	}
+
+	pub fn integer(n: i128) -> Self
+	{
+		Rational::new(n, 1)
+	}
+
+	fn overflow(positive: bool) -> Self
+	{
+		Rational { num: if positive { 1 } else { -1 }, den: 0 }
+	}
+
+	// Build a rational from the result of checked operations
+	fn checked(num: Option<i128>, den: Option<i128>, positive: bool) -> Self
+	{
+		match (num, den)
+		{
+			(Some(num), Some(den)) if den != 0 => Rational::new(num, den),
+			_ => Rational::overflow(positive)
+		}
+	}
+
+	pub fn numerator(&self) -> i128
+	{
+		self.num
+	}
+
+	pub fn denominator(&self) -> i128
+	{
+		self.den
+	}
+
+	pub fn is_integer(&self) -> bool
+	{
+		self.den == 1
+	}
+
+	pub fn is_finite(&self) -> bool
+	{
+		self.den != 0
+	}
+
+	pub fn to_f64(&self) -> f64
+	{
+		self.num as f64 / self.den as f64
+	}
+
+	// The simplest fraction equal to x up to rounding errors, found with its continued fraction
+	// ex: 0.3333333333333333 => 1/3
+	pub fn from_f64(x: f64) -> Option<Rational>
+	{
+		if !x.is_finite() || x.abs() > 1e15 { return None }
+
+		// h / k is the current convergent, h0 / k0 the previous one
+		let (mut h0, mut h, mut k0, mut k) = (0_i128, 1_i128, 1_i128, 0_i128);
+		let mut y = x;
+		for _ in 0..64
+		{
+			let a = y.floor();
+			let (h1, k1) = (a as i128 * h + h0, a as i128 * k + k0);
+			if k1 > MAX_DENOMINATOR { return None }
+			if (h1 as f64 / k1 as f64 - x).abs() <= 1e-12 * x.abs().max(1.0)
+			{
+				return Some(Rational::new(h1, k1));
+			}
+			h0 = h;
+			h = h1;
+			k0 = k;
+			k = k1;
+			y = 1.0 / (y - a);
+		}
+		None
+	}
+
+	// Read a decimal number exactly. ex: "3.25" => 13/4
+	pub fn parse(literal: &str) -> Option<Rational>
+	{
+		let (integer, decimals) = match literal.find('.')
+		{
+			Some(i) => (&literal[..i], &literal[i + 1..]),
+			None => (literal, "")
+		};
+		if integer.is_empty() || !(integer.chars().all(|c| c.is_ascii_digit()) && decimals.chars().all(|c| c.is_ascii_digit()))
+		{
+			return None;
+		}
+		let digits = format!("{}{}", integer, decimals);
+		let num = digits.parse::<i128>().ok();
+		let den = 10_i128.checked_pow(decimals.len() as u32);
+		Some(Rational::checked(num, den, true))
+	}
+}
+
+impl Default for Rational
+{
+	fn default() -> Self
+	{
+		Rational::integer(0)
+	}
+}
+
+impl Add for Rational
+{
+	type Output = Rational;
+
+	// a / b + c / d = (a * d + c * b) / (b * d)
+	fn add(self, other: Rational) -> Rational
+	{
+		if !self.is_finite() || !other.is_finite()
+		{
+			return Rational::overflow(self.to_f64() + other.to_f64() >= 0.0);
+		}
+		let num = self.num.checked_mul(other.den)
+			.and_then(|n| other.num.checked_mul(self.den).and_then(|m| n.checked_add(m)));
+		let den = self.den.checked_mul(other.den);
+		Rational::checked(num, den, self.to_f64() + other.to_f64() >= 0.0)
+	}
+}
+
+impl Sub for Rational
+{
+	type Output = Rational;
+
+	fn sub(self, other: Rational) -> Rational
+	{
+		self + (-other)
+	}
+}
+
+impl Mul for Rational
+{
+	type Output = Rational;
+
+	fn mul(self, other: Rational) -> Rational
+	{
+		let positive = (self.num >= 0) == (other.num >= 0);
+		if !self.is_finite() || !other.is_finite()
+		{
+			return Rational::overflow(positive);
+		}
+		// Reduce crosswise first to delay overflows
+		let g1 = gcd(self.num, other.den).max(1);
+		let g2 = gcd(other.num, self.den).max(1);
+		let num = (self.num / g1).checked_mul(other.num / g2);
+		let den = (self.den / g2).checked_mul(other.den / g1);
+		Rational::checked(num, den, positive)
+	}
+}
+
+impl Div for Rational
+{
+	type Output = Rational;
+
+	fn div(self, other: Rational) -> Rational
+	{
+		if other.num == 0 || !other.is_finite()
+		{
+			return match other.is_finite()
+			{
+				true => Rational::overflow(self.num >= 0),
+				false => Rational::integer(0)
+			};
+		}
+		self * Rational::new(other.den, other.num)
+	}
+}
+
+impl Neg for Rational
+{
+	type Output = Rational;
+
+	fn neg(self) -> Rational
+	{
+		match self.num.checked_neg()
+		{
+			Some(num) => Rational { num, den: self.den },
+			None => Rational::overflow(true)
+		}
+	}
+}
+
+impl PartialOrd for Rational
+{
+	fn partial_cmp(&self, other: &Rational) -> Option<Ordering>
+	{
+		match (self.num.checked_mul(other.den), other.num.checked_mul(self.den))
+		{
+			(Some(a), Some(b)) if self.is_finite() && other.is_finite() => a.partial_cmp(&b),
+			_ => self.to_f64().partial_cmp(&other.to_f64())
+		}
+	}
+}
+
+impl fmt::Display for Rational
+{
+	// ex: 2 | -7/3 | ∞
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		let to_display = match (self.den, self.num >= 0)
+		{
+			(0, true) => "∞".to_string(),
+			(0, false) => "-∞".to_string(),
+			(1, _) => format!("{}", self.num),
+			_ => format!("{}/{}", self.num, self.den)
+		};
+		write!(f, "{}", to_display)
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+
+	#[test]
+	fn reduced_fractions()
+	{
+		assert_eq!(Rational::new(6, -4), Rational::new(-3, 2));
+		assert_eq!(Rational::new(1, 3) + Rational::new(1, 6), Rational::new(1, 2));
+		assert_eq!(Rational::new(2, 3) / Rational::new(-4, 9), Rational::new(-3, 2));
+		assert_eq!(Rational::parse("3.25"), Some(Rational::new(13, 4)));
+		assert_eq!(Rational::from_f64(1.0 / 3.0), Some(Rational::new(1, 3)));
+		assert_eq!(Rational::new(-7, 3).to_string(), "-7/3");
+	}
+
+	#[test]
+	fn overflow()
+	{
+		let max = Rational::integer(i128::MAX);
+		let sum = max + Rational::integer(1);
+		assert!(!sum.is_finite());
+		assert_eq!(sum.to_string(), "∞");
+		assert_eq!((-max - Rational::integer(2)).to_string(), "-∞");
+		assert_eq!((max * Rational::integer(-2)).to_string(), "-∞");
+		// The digits of a literal that can't fit in an i128
+		assert!(!Rational::parse("1234567890123456789012345678901234567890").unwrap().is_finite());
+		assert!(!Rational::parse("0.1234567890123456789012345678901234567890").unwrap().is_finite());
+	}
+
+	#[test]
+	fn overflow_propagates()
+	{
+		let overflow = Rational::integer(i128::MAX) + Rational::integer(1);
+		assert!(!(overflow + Rational::integer(1)).is_finite());
+		assert!(!(overflow * Rational::new(1, 2)).is_finite());
+		assert_eq!(Rational::integer(1) / overflow, Rational::integer(0));
+		assert!(!(Rational::integer(1) / Rational::integer(0)).is_finite());
+	}
+
+	#[test]
+	fn min_overflows()
+	{
+		// -2^126 - 2^126 = i128::MIN, which can't be negated
+		let half = Rational::integer(i128::MIN / 2);
+		let min = half + half;
+		assert!(!min.is_finite() && min < Rational::integer(0));
+		assert!(!(-min).is_finite());
+		assert!(!Rational::new(1, i128::MIN).is_finite());
+		assert_eq!(gcd(i128::MIN, 6), 2);
+	}
+
+	#[test]
+	fn crosswise_reduction_delays_overflow()
+	{
+		// MAX / 2 × 2 / MAX = 1 without computing MAX × 2
+		assert_eq!(Rational::new(i128::MAX, 2) * Rational::new(2, i128::MAX), Rational::integer(1));
+		assert_eq!(Rational::new(i128::MAX, 2) / Rational::new(i128::MAX, 2), Rational::integer(1));
+	}
+}
@@ -0,0 +1,128 @@
+use std::io::{self, BufRead, Write};
+
+use computor::{precision, set_precision, Verbosity};
+
+use crate::{error_message, solve, Format, Options};
+
+const HELP: &str = "Type an equation or an inequality to solve it. ex: 5 * X^0 + 4 * X^1 = 4 * X^0
+Separate the equations of a linear system with ';'. ex: 2x + 3y = 5; x - y = 1
+End a line with '\\' to continue the expression on the next line
+
+Commands:
+  :help           Show this message
+  :verbose        Show or hide the explanation steps
+  :explain        Show or hide the reduction of the expression and the values put in each formula
+  :verify         Check the roots by evaluating the polynomial at each of them
+  :precision N    Display the numbers with N decimals
+  :history        Show the expressions solved so far
+  :quit           Leave the REPL (or Ctrl-D)";
+
+// The commands starting with ':', returns false when the REPL should stop
+fn run_command(command: &str, options: &mut Options, history: &[String]) -> bool
+{
+	let mut words = command.split_whitespace();
+	match (words.next(), words.next())
+	{
+		(Some(":help"), None) => println!("{}", HELP),
+		(Some(":verbose"), None) =>
+		{
+			options.verbosity = match options.verbosity
+			{
+				Verbosity::Quiet => Verbosity::Normal,
+				_ => Verbosity::Quiet
+			};
+			println!("Explanation steps are {}", if options.verbosity == Verbosity::Quiet { "hidden" } else { "shown" });
+		}
+		(Some(":explain"), None) =>
+		{
+			options.verbosity = match options.verbosity
+			{
+				Verbosity::Explain => Verbosity::Normal,
+				_ => Verbosity::Explain
+			};
+			println!("Detailed steps are {}", if options.verbosity == Verbosity::Explain { "shown" } else { "hidden" });
+		}
+		(Some(":verify"), None) =>
+		{
+			options.verify = !options.verify;
+			println!("Roots are {}", if options.verify { "verified" } else { "not verified" });
+		}
+		(Some(":precision"), None) => println!("Numbers are displayed with {} decimals", precision()),
+		(Some(":precision"), Some(n)) => match n.parse::<usize>()
+		{
+			Ok(n) if n <= 16 =>
+			{
+				set_precision(n);
+				println!("Numbers are displayed with {} decimals", n);
+			}
+			_ => eprintln!("Error: the precision must be a number between 0 and 16")
+		},
+		(Some(":history"), None) =>
+		{
+			for (i, expression) in history.iter().enumerate()
+			{
+				println!("{:>3}  {}", i + 1, expression);
+			}
+		}
+		(Some(":quit"), None) | (Some(":q"), None) => return false,
+		_ => eprintln!("Error: unknown command '{}', type :help to list the commands", command)
+	}
+	true
+}
+
+// Read the next expression, a line ending with '\' is continued on the next one
+// Returns None at the end of the input
+fn read_expression(lines: &mut impl Iterator<Item=io::Result<String>>) -> Option<String>
+{
+	let mut expression = String::new();
+	let mut prompt = "> ";
+	loop
+	{
+		print!("{}", prompt);
+		io::stdout().flush().ok()?;
+		let line = lines.next()?.ok()?;
+		match line.trim_end().strip_suffix('\\')
+		{
+			Some(start) =>
+			{
+				expression.push_str(start.trim_end());
+				expression.push(' ');
+				prompt = ". ";
+			}
+			None =>
+			{
+				expression.push_str(&line);
+				return Some(expression.trim().to_string());
+			}
+		}
+	}
+}
+
+// Solve the expressions typed by the user until :quit or the end of the input
+pub fn run(options: &mut Options)
+{
+	println!("computor REPL, type :help for the list of commands");
+	let stdin = io::stdin();
+	let mut lines = stdin.lock().lines();
+	let mut history: Vec<String> = vec![];
+	while let Some(expression) = read_expression(&mut lines)
+	{
+		if expression.is_empty() { continue }
+		if expression.starts_with(':')
+		{
+			if !run_command(&expression, options, &history) { return }
+			continue
+		}
+		if let Err(e) = solve(&expression, options)
+		{
+			match options.format
+			{
+				Format::Text | Format::Latex => eprintln!("{}", error_message(&e, &expression, options)),
+				Format::Json => println!("{}", error_message(&e, &expression, options))
+			}
+		}
+		history.push(expression);
+		println!();
+	}
+	println!();
+}
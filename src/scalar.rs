@@ -0,0 +1,62 @@
+use std::fmt::Debug;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+use crate::format_number;
+use crate::rational::Rational;
+
+// A float smaller than this fraction of the numbers it is computed from is a rounding error
+const NULL_TOLERANCE: f64 = 1e-10;
+
+// The numbers the parser and the polynomials can be computed with:
+// f64 by default, Rational in exact mode
+pub trait Scalar: Copy + Debug + PartialEq + PartialOrd
+	+ Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> + Div<Output=Self> + Neg<Output=Self>
+{
+	fn zero() -> Self;
+	fn one() -> Self;
+
+	// Read a number literal. ex: "4.5"
+	fn from_literal(literal: &str) -> Option<Self>;
+
+	fn to_f64(&self) -> f64;
+	fn is_finite(&self) -> bool;
+
+	// True when the number is 0 up to the rounding errors of a computation on numbers as large as size
+	// ex: 1e-17 computed from numbers around 1 is null in f64, a rational is only null when it is 0
+	fn is_null(&self, size: f64) -> bool;
+
+	// The text displayed in the reduced form and the solutions
+	fn format(&self) -> String;
+}
+
+impl Scalar for f64
+{
+	fn zero() -> Self { 0.0 }
+	fn one() -> Self { 1.0 }
+
+	fn from_literal(literal: &str) -> Option<Self>
+	{
+		literal.parse::<f64>().ok()
+	}
+
+	fn to_f64(&self) -> f64 { *self }
+	fn is_finite(&self) -> bool { f64::is_finite(*self) }
+	fn is_null(&self, size: f64) -> bool { self.abs() <= NULL_TOLERANCE * size }
+	fn format(&self) -> String { format_number(*self) }
+}
+
+impl Scalar for Rational
+{
+	fn zero() -> Self { Rational::integer(0) }
+	fn one() -> Self { Rational::integer(1) }
+
+	fn from_literal(literal: &str) -> Option<Self>
+	{
+		Rational::parse(literal)
+	}
+
+	fn to_f64(&self) -> f64 { Rational::to_f64(self) }
+	fn is_finite(&self) -> bool { Rational::is_finite(self) }
+	fn is_null(&self, _size: f64) -> bool { *self == Rational::integer(0) }
+	fn format(&self) -> String { format!("{}", self) }
+}
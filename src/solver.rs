@@ -1,231 +1,1182 @@
-use crate::Comparison;
-use crate::{abs, format_number};
+use std::fmt;
 
-fn solution_degree_0(sol: f64, cmp_token: &Comparison)
+use crate::{Comparison, Complex, ComputorError, Polynomial, Scalar};
+use crate::interval::{Bound, Interval, SolutionSet};
+use crate::forms::factor;
+use crate::numeric;
+use crate::steps::{StepLog, Verbosity};
+use crate::radical::{self, Surd};
+use crate::{abs, format_number, latex_number, Latex};
+
+// How the equations of degree 2 are solved in the explanation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method
 {
-	match cmp_token
+	// S1 = (-b - √Δ) / 2a
+	Discriminant,
+
+	// Completing the square: (X + b / 2a)² = (b / 2a)² - c / a
+	Square
+}
+
+// The result of solving P(X) cmp 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution
+{
+	// The name of the unknown as it was written, ex: x | t
+	pub variable: String,
+
+	pub degree: u32,
+	pub comparison: Comparison,
+
+	// Δ of the equations of degree 2 and 3, None otherwise
+	pub discriminant: Option<f64>,
+
+	// Δ = discriminant × discriminant_factor², the factor is 1 unless b² - 4ac overflows or the roots
+	// of a cubic are too far from 1: discriminant is then the one of a, b and c divided by the factor,
+	// or the one of P(sU) and the factor is s³
+	pub discriminant_factor: f64,
+
+	// The roots of P(X) = 0, real or complex, in the order they were found
+	// A root of multiplicity k appears k times
+	pub roots: Vec<Complex>,
+
+	// The root of an equation of degree 1 written with the scalar of the computation, None for the other degrees
+	// ex: 7/3 in exact mode, where the root is 2.33 in roots
+	pub exact_root: Option<String>,
+
+	// The real values of X verifying the comparison, None when they can't be found
+	pub set: Option<SolutionSet>,
+
+	// True when the roots are numeric approximations
+	pub approximate: bool,
+
+	// An estimate of the forward error of each root, in the order of roots
+	// Only computed for the equations of degree 2, empty otherwise
+	pub errors: Vec<f64>,
+
+	// The explanation of the computation recorded at the verbosity given to get_solution
+	pub steps: StepLog
+}
+
+impl Solution
+{
+	fn new(variable: &str, degree: u32, comparison: Comparison, verbosity: Verbosity) -> Self
+	{
+		Solution { variable: variable.to_string(), degree, comparison, discriminant: None, discriminant_factor: 1.0, roots: vec![], exact_root: None, set: None,
+			approximate: false,
+			errors: vec![], steps: StepLog::new(verbosity) }
+	}
+
+	fn step(&mut self, step: String)
+	{
+		self.steps.record(Verbosity::Normal, step);
+	}
+
+	// A formula and its value, the values substituted in the formula are only shown when explaining
+	// ex: Δ = b² - 4ac = 2² - 4 × 1 × (-3) = 16
+	fn formula(&mut self, formula: &str, substituted: String, value: String)
+	{
+		let step = match self.steps.explains()
+		{
+			true => format!("{} = {} = {}", formula, substituted, value),
+			false => format!("{} = {}", formula, value)
+		};
+		self.step(step);
+	}
+
+	// The unknown as written in the solution text, x is written X
+	fn unknown(&self) -> String
+	{
+		match self.variable.as_str()
+		{
+			"x" => "X".to_string(),
+			name => name.to_string()
+		}
+	}
+
+	// True when some roots of P(X) = 0 are not real numbers
+	pub fn has_complex_roots(&self) -> bool
 	{
-		Comparison::Equal if sol == 0.0		=> println!("The solution is X ∈ ℝ"),
-		Comparison::Greater if sol > 0.0	=> println!("The solution is X ∈ ℝ"),
-		Comparison::GreaterEq if sol >= 0.0	=> println!("The solution is X ∈ ℝ"),
-		Comparison::Lower if sol < 0.0		=> println!("The solution is X ∈ ℝ"),
-		Comparison::LowerEq if sol <= 0.0	=> println!("The solution is X ∈ ℝ"),
-		_ => println!("There is no solution in ℝ")
+		self.roots.iter().any(|s| !s.is_real())
 	}
 }
 
-fn solution_degree_1(sol: f64, positive: bool, cmp_token: &Comparison)
+// A number substituted in a formula, in parentheses when it is negative. ex: -3 => (-3)
+fn operand(n: String) -> String
+{
+	match n.starts_with('-')
+	{
+		true => format!("({})", n),
+		false => n
+	}
+}
+
+// The solutions of P(X) cmp 0 built from the set where P(X) > 0 and the set where P(X) = 0
+fn comparison_set(cmp_token: &Comparison, positive: &SolutionSet, zeros: &SolutionSet) -> Option<SolutionSet>
 {
 	match cmp_token
 	{
-		Comparison::Equal =>
+		Comparison::Equal => Some(zeros.normalize()),
+		Comparison::Greater => Some(positive.normalize()),
+		Comparison::GreaterEq => Some(positive.union(zeros)),
+		Comparison::Lower => Some(positive.union(zeros).complement()),
+		Comparison::LowerEq => Some(positive.complement()),
+		Comparison::No => None
+	}
+}
+
+fn solution_degree_0<T: Scalar>(sol: T, cmp_token: &Comparison) -> Option<SolutionSet>
+{
+	let positive = if sol > T::zero() { SolutionSet::All } else { SolutionSet::Empty };
+	let zeros = if sol == T::zero() { SolutionSet::All } else { SolutionSet::Empty };
+	comparison_set(cmp_token, &positive, &zeros)
+}
+
+fn solution_degree_1<T: Scalar>(solution: &mut Solution, a: T, b: T)
+{
+	let (sol, positive) = (-b / a, a > T::zero());
+	let formula = format!("When Y = 0, {} = -b / a", solution.unknown());
+	solution.formula(&formula, format!("-{} / {}", operand(b.format()), operand(a.format())), sol.format());
+	// The solution set is in f64, the exact root is kept for the conclusion
+	let x = sol.to_f64();
+	solution.exact_root = Some(sol.format());
+	solution.roots.push(Complex::real(x));
+
+	// aX + b > 0 on the side of the root where aX grows
+	let greater = match positive
+	{
+		true => Interval::new(Bound::Open(x), Bound::Infinite),
+		false => Interval::new(Bound::Infinite, Bound::Open(x))
+	};
+	let positive = SolutionSet::from_intervals(vec![greater]);
+	solution.set = comparison_set(&solution.comparison, &positive, &SolutionSet::Values(vec![x]));
+}
+
+// The roots of a quadratic equation as fractions or with radicals when they are not integers
+fn exact_form(exact: Option<Surd>, x: &str) -> Option<String>
+{
+	let surd = exact?;
+	match surd.rational_roots()
+	{
+		Some((s1, s2)) if s1 == s2 =>
+		{
+			if !s1.is_integer() { Some(format!("In exact form, {} = {}", x, s1)) } else { None }
+		}
+		Some((s1, s2)) =>
 		{
-			println!("When Y = 0, X = -b / a = {0}\nHence the solution is X = {0}", format_number(sol));
+			if !s1.is_integer() || !s2.is_integer() { Some(format!("In exact form, {} = {{{}, {}}}", x, s1, s2)) } else { None }
 		}
+		None => Some(format!("In exact form, {} = {}", x, surd))
+	}
+}
 
-		Comparison::Greater =>
+// The coefficients divided by the power of 2 closest to the largest one and this power
+// The division is exact, it keeps the roots and b² - 4ac can't overflow anymore
+// ex: (1, 1e200, 1) => (7.5e-201, 0.75, 7.5e-201), 1.3e200
+pub(crate) fn scale(a: f64, b: f64, c: f64) -> (f64, f64, f64, f64)
+{
+	let largest = abs(a).max(abs(b)).max(abs(c));
+	let factor = 2_f64.powi((largest.log2().floor() as i32).clamp(-1022, 1023));
+	(a / factor, b / factor, c / factor, factor)
+}
+
+// The roots of the closed forms of degree 3 and 4 are computed for P(X) where X = sU when they are
+// further than this from 1, so that the powers of the coefficients in p, q, r and Δ can't overflow
+const ROOT_RANGE: f64 = 1.1259e15;
+
+// The polynomial in U where X = sU, divided by a power of 2 close to its largest coefficient, and s:
+// s is a power of 2 close to the bound of the roots when it is out of ROOT_RANGE, 1 otherwise
+// The powers of 2 keep the roots exactly, they are multiplied by s
+// ex: x³ - 1e110x => 0.5U³ - 0.5U, s = 2^183 = 1.23e55
+fn substitution(poly: &Polynomial) -> (Polynomial, f64)
+{
+	let bound = numeric::fujiwara_bound(poly);
+	let s = match bound == 0.0 || (1.0 / ROOT_RANGE..=ROOT_RANGE).contains(&bound)
+	{
+		true => 1.0,
+		false => 2_f64.powi((bound.log2().round() as i32).clamp(-1022, 1023))
+	};
+	// a[i]Xⁱ = a[i]sⁱUⁱ, the polynomial is divided by sⁿ so that no power of s overflows
+	let degree = poly.degree() as usize;
+	let coefficients: Vec<f64> = poly.coefficients().iter().enumerate()
+		.map(|(i, n)| (i..degree).fold(*n, |n, _| n / s))
+		.collect();
+	let largest = coefficients.iter().fold(0.0, |max: f64, n| max.max(abs(*n)));
+	let factor = 2_f64.powi((largest.log2().floor() as i32).clamp(-1022, 1023));
+	(Polynomial::from_coefficients(coefficients).scale(1.0 / factor), s)
+}
+
+// A number computed with coefficients divided by factor, n × factor² when the factor is not 1
+// ex: 7.65e199² × 1.71
+fn scaled_number(n: f64, factor: f64) -> String
+{
+	match factor == 1.0
+	{
+		true => format_number(n),
+		false => format!("{}² × {}", format_number(factor), operand(format_number(n)))
+	}
+}
+
+// The real roots of aX² + bX + c = 0 in the order (-b - √Δ) / 2a, (-b + √Δ) / 2a
+// -b + √Δ cancels when b² ≫ 4ac, so only the root where -b and ±√Δ have the same sign
+// is computed with this formula, the other one comes from S1 * S2 = c / a (citardauq)
+// ex: x² + 1e8x + 1 => S1 = -1e8, S2 = 1 / -1e8 instead of 0
+pub(crate) fn stable_roots(a: f64, b: f64, c: f64, sqrt_delta: f64) -> (f64, f64)
+{
+	let q = -(b + sqrt_delta.copysign(b)) / 2.0;
+	match (q == 0.0, b.is_sign_positive())
+	{
+		(true, _) => (0.0, 0.0),
+		(false, true) => (q / a, c / q),
+		(false, false) => (c / q, q / a)
+	}
+}
+
+// An estimate of |S - r| between a computed root r of aX² + bX + c and the exact root S:
+// a rounding error of the coefficients changes P(r) by about ε(|a||r|² + |b||r| + |c|),
+// which moves a simple root by this change divided by |P'(r)| and a double root by its square root divided by |a|
+// The change is computed divided by |r| when |r| > 1 so that |r|² can't overflow, then multiplied back
+fn root_error(a: f64, b: f64, c: f64, r: Complex) -> f64
+{
+	let m = r.modulus();
+	let size = m.max(1.0);
+	let change = f64::EPSILON * (abs(a) * m * (m / size) + abs(b) * (m / size) + abs(c) / size);
+	let derivative = Complex::new(2.0 * a * r.re + b, 2.0 * a * r.im).modulus();
+	let simple = change / derivative * size;
+	match simple * abs(a) < derivative
+	{
+		true => simple,
+		false => (change / abs(a)).sqrt() * size.sqrt()
+	}
+}
+
+// The roots from the discriminant, delta is the one of the scaled coefficients
+// ex: S1 = (-b - √Δ) / 2a = -2
+fn discriminant_steps(solution: &mut Solution, (a, b, c): (f64, f64, f64), delta: f64)
+{
+	let x = solution.unknown();
+	let (s1, s2) = (solution.roots[0], solution.roots[1]);
+	let number = |n: f64| operand(format_number(n));
+	let positive = b.is_sign_positive();
+	let root = match solution.discriminant_factor == 1.0
+	{
+		true => format_number(abs(solution.discriminant.unwrap_or(delta))),
+		false => format!("({})", scaled_number(abs(delta), solution.discriminant_factor))
+	};
+	let (a, b, c) = (number(a), number(b), number(c));
+	if delta > 0.0
+	{
+		solution.step("The discriminant is strictly positive".to_string());
+		solution.step(format!("When Y = 0, {} = {{S1, S2}} with", x));
+		match positive
 		{
-			println!("When Y = 0, X = -b / a = {0}", format_number(sol));
-			match positive
+			true =>
 			{
-				true => println!("Hence the solution is X ∈ ({0}, ∞)", format_number(sol)),
-				false =>  println!("Hence the solution is X ∈ (-∞, {0})", format_number(sol)),
+				solution.formula("S1 = (-b - √Δ) / 2a", format!("(-{} - √{}) / (2 × {})", b, root, a), format_number(s1.re));
+				solution.formula("S2 = 2c / (-b - √Δ)", format!("2 × {} / (-{} - √{})", c, b, root), format_number(s2.re));
 			}
-		}
-		Comparison::GreaterEq =>
-		{
-			println!("When Y = 0, X = -b / a = {0}", format_number(sol));
-			match positive
+			false =>
 			{
-				true => println!("Hence the solution is X ∈ [{0}, ∞)", format_number(sol)),
-				false =>  println!("Hence the solution is X ∈ (-∞, {0}]", format_number(sol)),
+				solution.formula("S1 = 2c / (-b + √Δ)", format!("2 × {} / (-{} + √{})", c, b, root), format_number(s1.re));
+				solution.formula("S2 = (-b + √Δ) / 2a", format!("(-{} + √{}) / (2 × {})", b, root, a), format_number(s2.re));
 			}
 		}
-		Comparison::Lower =>
+	}
+	else if delta == 0.0
+	{
+		solution.step("The discriminant is null".to_string());
+		solution.formula(&format!("When Y = 0, {} = -b / 2a", x), format!("-{} / (2 × {})", b, a), format_number(s1.re));
+	}
+	else
+	{
+		solution.step("The discriminant is strictly negative".to_string());
+		solution.step(format!("When Y = 0, {} = {{S1, S2}} with", x));
+		solution.formula("S1 = (-b - i√|Δ|) / 2a", format!("(-{} - i√{}) / (2 × {})", b, root, a), s1.to_string());
+		solution.formula("S2 = (-b + i√|Δ|) / 2a", format!("(-{} + i√{}) / (2 × {})", b, root, a), s2.to_string());
+	}
+}
+
+// The roots from aX² + bX + c = a((X + b / 2a)² - ((b / 2a)² - c / a))
+// ex: (X + 1)² = 4
+fn square_steps(solution: &mut Solution, (a, b, c): (f64, f64, f64), delta: f64)
+{
+	let x = solution.unknown();
+	let (s1, s2) = (solution.roots[0], solution.roots[1]);
+	let half = b / (2.0 * a);
+	let right = half * half - c / a;
+	let number = |n: f64| operand(format_number(n));
+	solution.step(format!("Divide by a: {0}² + (b / a){0} + c / a = 0", x));
+	solution.step(format!("{} = 0", Polynomial::from_coefficients(vec![c / a, b / a, 1.0]).display_with(&x)));
+	solution.step(format!("Complete the square: ({} + b / 2a)² = (b / 2a)² - c / a", x));
+	solution.formula("b / 2a", format!("{} / (2 × {})", number(b), number(a)), format_number(half));
+	solution.formula("(b / 2a)² - c / a", format!("{}² - {} / {}", number(half), number(c), number(a)), format_number(right));
+	solution.step(format!("Hence {}² = {}", factor(&x, &format_number(-half)), format_number(right)));
+	solution.step(String::new());
+	let (half, root) = (number(half), format_number(abs(right)));
+	if delta > 0.0
+	{
+		solution.step("The right side is strictly positive".to_string());
+		solution.step(format!("When Y = 0, {} = {{S1, S2}} with", x));
+		solution.formula("S1 = -b / 2a - √((b / 2a)² - c / a)", format!("-{} - √{}", half, root), format_number(s1.re));
+		solution.formula("S2 = -b / 2a + √((b / 2a)² - c / a)", format!("-{} + √{}", half, root), format_number(s2.re));
+	}
+	else if delta == 0.0
+	{
+		solution.step("The right side is null".to_string());
+		solution.formula(&format!("When Y = 0, {} = -b / 2a", x), format!("-{}", half), format_number(s1.re));
+	}
+	else
+	{
+		solution.step("The right side is strictly negative".to_string());
+		solution.step(format!("When Y = 0, {} = {{S1, S2}} with", x));
+		solution.formula("S1 = -b / 2a - i√|(b / 2a)² - c / a|", format!("-{} - i√{}", half, root), s1.to_string());
+		solution.formula("S2 = -b / 2a + i√|(b / 2a)² - c / a|", format!("-{} + i√{}", half, root), s2.to_string());
+	}
+}
+
+// The roots are computed with the scaled coefficients, they are the same as the ones of a, b and c
+fn solution_degree_2(solution: &mut Solution, (a, b, c): (f64, f64, f64), exact: Option<Surd>, method: Method)
+{
+	let x = solution.unknown();
+	let original = (a, b, c);
+	let (a, b, c, _) = scale(a, b, c);
+	let delta = (b * b) - (4.0 * a * c);
+	if delta > 0.0
+	{
+		let (s1, s2) = stable_roots(a, b, c, delta.sqrt());
+		solution.roots = vec![Complex::real(s1), Complex::real(s2)];
+	}
+	else if delta == 0.0
+	{
+		let s = -b / (2.0 * a);
+		solution.roots = vec![Complex::real(s), Complex::real(s)];
+	}
+	else
+	{
+		let re = -b / (2.0 * a);
+		let im = abs(abs(delta).sqrt() / (2.0 * a));
+		solution.roots = vec![Complex::new(re, -im), Complex::new(re, im)];
+	}
+	// The real roots are in the order of (-b ∓ √Δ) / 2a, -b / 2a ∓ √((b / 2a)² - c / a) is the other way around when a < 0
+	if method == Method::Square && a < 0.0 && delta > 0.0
+	{
+		solution.roots.swap(0, 1);
+	}
+	match method
+	{
+		Method::Discriminant => discriminant_steps(solution, original, delta),
+		Method::Square => square_steps(solution, original, delta)
+	}
+	if let Some(step) = exact_form(exact, &x) { solution.step(step) }
+	solution.errors = solution.roots.iter().map(|r| root_error(a, b, c, *r)).collect();
+	match delta == 0.0
+	{
+		true => solution.step(format!("Estimated forward error: |ΔX| ≈ {}", format_number(solution.errors[0]))),
+		false =>
 		{
-			println!("When Y = 0, X = -b / a = {0}", format_number(sol));
-			match positive
-			{
-				true => println!("Hence the solution is X ∈ (-∞, {0})", format_number(sol)),
-				false =>  println!("Hence the solution is X ∈ ({0}, ∞)", format_number(sol)),
-			}
+			let errors: Vec<String> = solution.errors.iter().enumerate()
+				.map(|(i, e)| format!("|ΔS{}| ≈ {}", i + 1, format_number(*e)))
+				.collect();
+			solution.step(format!("Estimated forward error: {}", errors.join(", ")));
+			solution.step(String::new());
+		}
+	}
+
+	// aX² + bX + c has the sign of a outside of the real roots and the opposite sign between them
+	let mut real: Vec<f64> = solution.roots.iter().filter(|s| s.is_real()).map(|s| s.re).collect();
+	real.sort_by(|s1, s2| s1.partial_cmp(s2).unwrap());
+	let zeros = SolutionSet::from_intervals(real.iter().map(|s| Interval::point(*s)).collect());
+	let positive = match (real.first(), real.last(), a > 0.0)
+	{
+		(Some(s1), Some(s2), true) => SolutionSet::from_intervals(vec![Interval::new(Bound::Closed(*s1), Bound::Closed(*s2))]).complement(),
+		(Some(s1), Some(s2), false) => SolutionSet::from_intervals(vec![Interval::new(Bound::Open(*s1), Bound::Open(*s2))]),
+		(_, _, true) => SolutionSet::All,
+		(_, _, false) => SolutionSet::Empty
+	};
+	solution.set = comparison_set(&solution.comparison, &positive, &zeros);
+}
+
+// A value computed from terms of this size or less is null up to NULL_TOLERANCE times this size
+const NULL_TOLERANCE: f64 = 1e-10;
+
+// Rounding errors make an exact comparison with 0 unreliable: p and Δ of a cubic are null when they are
+// smaller than the error made computing them, which grows with the terms they are computed from
+// Returns (p is null, Δ is null)
+// ex: Δ = -3.70e-14 is not null for the roots 0.01, 0.02 and 0.03 since p = -1e-4 is small too
+fn cubic_nulls((a, b, c, d): (f64, f64, f64, f64), p: f64, q: f64, delta: f64) -> (bool, bool)
+{
+	let p_error = NULL_TOLERANCE * abs(c / a).max(b * b / (3.0 * a * a));
+	let q_error = NULL_TOLERANCE * abs(2.0 * b * b * b).max(abs(9.0 * a * b * c)).max(abs(27.0 * a * a * d)) / abs(27.0 * a * a * a);
+	let delta_error = abs(q) / 2.0 * q_error + p * p / 9.0 * p_error;
+	(abs(p) <= p_error, abs(delta) <= delta_error)
+}
+
+// Two real roots closer than MERGE_TOLERANCE times the largest root are the same root approximated twice
+const MERGE_TOLERANCE: f64 = 1e-6;
+
+// The distinct real roots sorted by increasing order with their multiplicity
+fn real_roots(roots: &[Complex]) -> Vec<(f64, usize)>
+{
+	let mut real: Vec<f64> = roots.iter().filter(|s| s.is_real()).map(|s| s.re).collect();
+	let scale = roots.iter().fold(0.0, |max: f64, s| max.max(s.modulus()));
+	real.sort_by(|s1, s2| s1.total_cmp(s2));
+	let mut distinct: Vec<(f64, usize)> = vec![];
+	for s in real
+	{
+		match distinct.last_mut()
+		{
+			Some((root, k)) if s - *root <= MERGE_TOLERANCE * scale => *k += 1,
+			_ => distinct.push((s, 1))
+		}
+	}
+	distinct
+}
+
+// The sign of P on each interval between two consecutive real roots, found at a point inside it
+// ex: (x + 1)(x - 2)² => [-, +, +]
+fn interval_signs(poly: &Polynomial, real: &[(f64, usize)]) -> Vec<bool>
+{
+	let mut points: Vec<f64> = vec![];
+	match (real.first(), real.last())
+	{
+		(Some((first, _)), Some((last, _))) =>
+		{
+			points.push(first - 1.0);
+			points.extend(real.windows(2).map(|w| (w[0].0 + w[1].0) / 2.0));
+			points.push(last + 1.0);
 		}
-		Comparison::LowerEq =>
+		_ => points.push(0.0)
+	}
+	points.iter().map(|x| numeric::eval(poly, Complex::real(*x)).re > 0.0).collect()
+}
+
+// The sign table of P, one column per real root and one per interval between them
+// ex:	X    | -∞       -1       2       ∞
+//		P(X) |      -    0   +   0   +
+fn sign_table(x: &str, real: &[(f64, usize)], signs: &[bool]) -> Vec<String>
+{
+	let mut columns: Vec<(String, String)> = vec![("-∞".to_string(), String::new())];
+	for (i, positive) in signs.iter().enumerate()
+	{
+		columns.push((String::new(), if *positive { "+" } else { "-" }.to_string()));
+		if let Some((root, _)) = real.get(i)
 		{
-			println!("When Y = 0, X = -b / a = {0}", format_number(sol));
-			match positive
+			columns.push((format_number(*root), "0".to_string()));
+		}
+	}
+	columns.push(("∞".to_string(), String::new()));
+	let p = format!("P({})", x);
+	let label = p.chars().count() + 1;
+	let (mut top_row, mut bottom_row) = (format!("{:<label$}|", x, label = label), format!("{} |", p));
+	for (top, bottom) in columns
+	{
+		let width = top.chars().count().max(bottom.chars().count()) + 2;
+		top_row.push_str(&format!("{:^width$}", top, width = width));
+		bottom_row.push_str(&format!("{:^width$}", bottom, width = width));
+	}
+	vec![top_row.trim_end().to_string(), bottom_row.trim_end().to_string()]
+}
+
+// The real solutions of an equation or an inequality of degree 3 or more
+// An inequality is solved with the sign chart of P built from its real roots
+fn real_solution_set(solution: &mut Solution, poly: &Polynomial)
+{
+	let real = real_roots(&solution.roots);
+	let zeros = SolutionSet::from_intervals(real.iter().map(|(s, _)| Interval::point(*s)).collect());
+	if solution.comparison == Comparison::Equal
+	{
+		solution.set = Some(zeros);
+		return
+	}
+	let signs = interval_signs(poly, &real);
+	if solution.steps.lines().last().is_some_and(|step| !step.is_empty())
+	{
+		solution.step(String::new());
+	}
+	let x = solution.unknown();
+	solution.step(format!("Sign table of P({}):", x));
+	for line in sign_table(&x, &real, &signs)
+	{
+		solution.step(line);
+	}
+	for (root, k) in &real
+	{
+		if k % 2 == 0
+		{
+			solution.step(format!("{} is a root of even multiplicity {}, the sign does not change", format_number(*root), k));
+		}
+	}
+	solution.step(String::new());
+
+	// The intervals where P(X) > 0, bounded by the real roots
+	let mut bounds: Vec<Bound> = vec![Bound::Infinite];
+	bounds.extend(real.iter().map(|(s, _)| Bound::Open(*s)));
+	bounds.push(Bound::Infinite);
+	let positive: Vec<Interval> = signs.iter().enumerate()
+		.filter(|(_, positive)| **positive)
+		.map(|(i, _)| Interval::new(bounds[i], bounds[i + 1]))
+		.collect();
+	solution.set = comparison_set(&solution.comparison, &SolutionSet::from_intervals(positive), &zeros);
+}
+
+// Cardano's method on the depressed cubic t³ + pt + q = 0 where x = t - b / 3a
+// The noise of the roots of scaled, whose coefficients are a, b, c and d, is removed before they are multiplied by unit
+fn solution_degree_3(solution: &mut Solution, (a, b, c, d): (f64, f64, f64, f64), p: f64, q: f64, delta: f64, unit: f64, scaled: &Polynomial)
+{
+	let x = solution.unknown();
+	let shift = -b / (3.0 * a);
+	let mut sol: Vec<Complex> = vec![];
+	let clean = |root: Complex| numeric::clean_root(root, scaled) * Complex::real(unit);
+	let (p_null, delta_null) = cubic_nulls((a, b, c, d), p, q, delta);
+	if delta_null
+	{
+		solution.step("The discriminant is null".to_string());
+		if p_null
+		{
+			sol = vec![clean(Complex::real(shift)); 3];
+			solution.step(format!("When Y = 0, {} = -b / 3a = {} (triple root)", x, sol[0]));
+		}
+		else
+		{
+			sol.push(clean(Complex::real(3.0 * q / p + shift)));
+			sol.push(clean(Complex::real(-3.0 * q / (2.0 * p) + shift)));
+			solution.step(format!("When Y = 0, {} = {{S1, S2}} with", x));
+			solution.step(format!("S1 = 3q / p - b / 3a = {}", sol[0]));
+			solution.step(format!("S2 = -3q / 2p - b / 3a = {} (double root)", sol[1]));
+			solution.step(String::new());
+			sol.push(sol[1]);
+		}
+	}
+	else if delta > 0.0
+	{
+		solution.step("The discriminant is strictly positive".to_string());
+		let u = (-q / 2.0 + delta.sqrt()).cbrt();
+		let v = (-q / 2.0 - delta.sqrt()).cbrt();
+		solution.step(format!("u = ∛(-q / 2 + √Δ) = {}", format_number(u)));
+		solution.step(format!("v = ∛(-q / 2 - √Δ) = {}", format_number(v)));
+		let complex = clean(Complex::new(-(u + v) / 2.0 + shift, 3.0_f64.sqrt() * (u - v) / 2.0));
+		sol.push(clean(Complex::real(u + v + shift)));
+		sol.push(complex);
+		sol.push(complex.conjugate());
+		solution.step(format!("When Y = 0, {} = {{S1, S2, S3}} with", x));
+		solution.step(format!("S1 = u + v - b / 3a = {}", sol[0]));
+		solution.step(format!("S2 = -(u + v) / 2 - b / 3a + i√3(u - v) / 2 = {}", sol[1]));
+		solution.step(format!("S3 = -(u + v) / 2 - b / 3a - i√3(u - v) / 2 = {}", sol[2]));
+		solution.step(String::new());
+	}
+	else
+	{
+		solution.step("The discriminant is strictly negative".to_string());
+		let r = 2.0 * (-p / 3.0).sqrt();
+		let cos = (3.0 * q / (2.0 * p) * (-3.0 / p).sqrt()).clamp(-1.0, 1.0);
+		let theta = cos.acos() / 3.0;
+		solution.step(format!("θ = arccos(3q / 2p * √(-3 / p)) / 3 = {}", format_number(theta)));
+		solution.step(format!("When Y = 0, {} = {{S1, S2, S3}} with", x));
+		for k in 0..3
+		{
+			sol.push(clean(Complex::real(r * (theta - 2.0 * std::f64::consts::PI * k as f64 / 3.0).cos() + shift)));
+			solution.step(format!("S{} = 2√(-p / 3) cos(θ - {}π / 3) - b / 3a = {}", k + 1, 2 * k, sol[k]));
+		}
+		solution.step(String::new());
+	}
+	solution.roots = sol;
+}
+
+// The real roots of ax³ + bx² + cx + d with Cardano's method, without any explanation
+fn real_cubic_roots(a: f64, b: f64, c: f64, d: f64) -> Vec<f64>
+{
+	let shift = -b / (3.0 * a);
+	let p = (3.0 * a * c - b * b) / (3.0 * a * a);
+	let q = (2.0 * b * b * b - 9.0 * a * b * c + 27.0 * a * a * d) / (27.0 * a * a * a);
+	let delta = (q * q) / 4.0 + (p * p * p) / 27.0;
+	let (p_null, delta_null) = cubic_nulls((a, b, c, d), p, q, delta);
+	if delta_null && p_null
+	{
+		vec![shift]
+	}
+	else if delta_null
+	{
+		vec![3.0 * q / p + shift, -3.0 * q / (2.0 * p) + shift]
+	}
+	else if delta > 0.0
+	{
+		vec![(-q / 2.0 + delta.sqrt()).cbrt() + (-q / 2.0 - delta.sqrt()).cbrt() + shift]
+	}
+	else
+	{
+		let r = 2.0 * (-p / 3.0).sqrt();
+		let theta = (3.0 * q / (2.0 * p) * (-3.0 / p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+		(0..3).map(|k| r * (theta - 2.0 * std::f64::consts::PI * k as f64 / 3.0).cos() + shift).collect()
+	}
+}
+
+// The roots of y² + by + c = 0
+fn quadratic_roots(b: f64, c: f64) -> Vec<Complex>
+{
+	let delta = b * b - 4.0 * c;
+	match delta >= 0.0 || abs(delta) <= NULL_TOLERANCE * (b * b).max(abs(4.0 * c))
+	{
+		true =>
+		{
+			let (y1, y2) = stable_roots(1.0, b, c, delta.max(0.0).sqrt());
+			vec![Complex::real(y1), Complex::real(y2)]
+		}
+		false => vec![Complex::new(-b / 2.0, -(-delta).sqrt() / 2.0), Complex::new(-b / 2.0, (-delta).sqrt() / 2.0)]
+	}
+}
+
+// Ferrari's method on the depressed quartic y⁴ + py² + qy + r = 0 where x = y - b / 4a
+// The noise of the roots of scaled, whose coefficients are a, b, c and d, is removed before they are multiplied by unit
+fn solution_degree_4(solution: &mut Solution, (a, b, c, d): (f64, f64, f64, f64), p: f64, q: f64, r: f64, unit: f64, scaled: &Polynomial)
+{
+	let shift = -b / (4.0 * a);
+	let shift = Complex::real(shift);
+	let mut sol: Vec<Complex> = vec![];
+	// Like p and Δ of a cubic, q is null when smaller than the rounding error of its terms
+	let q_error = NULL_TOLERANCE * abs(b * b * b).max(abs(4.0 * a * b * c)).max(abs(8.0 * a * a * d)) / abs(8.0 * a * a * a);
+	if abs(q) <= q_error
+	{
+		// y⁴ + py² + r = 0 is a quadratic equation in z = y²
+		solution.step("q is null so the equation is biquadratic: z² + pz + r = 0 with z = y²".to_string());
+		let delta = p * p - 4.0 * r;
+		solution.formula("Δ = p² - 4r", format!("{}² - 4 × {}", operand(format_number(p)), operand(format_number(r))), format_number(delta));
+		for z in quadratic_roots(p, r)
+		{
+			match z.is_real() && z.re < 0.0
 			{
-				true => println!("Hence the solution is X ∈ (-∞, {0}]", format_number(sol)),
-				false =>  println!("Hence the solution is X ∈ [{0}, ∞)", format_number(sol)),
+				true => solution.step(format!("z = {} => y = ±i√-z", z)),
+				false => solution.step(format!("z = {} => y = ±√z", z))
 			}
+			sol.push(z.sqrt() + shift);
+			sol.push(-z.sqrt() + shift);
+		}
+	}
+	else
+	{
+		// The resolvent cubic always has a strictly positive root when q is not null
+		// It is polished since q / 2√(2m) needs all of its digits when m is lost in the rounding of Cardano's method
+		// ex: 8m³ + 2.4m - 1.6e-41 => 0 instead of 6.7e-42
+		solution.step("Resolvent cubic: 8m³ + 8pm² + (2p² - 8r)m - q² = 0".to_string());
+		let resolvent = Polynomial::from_coefficients(vec![-q * q, 2.0 * p * p - 8.0 * r, 8.0 * p, 8.0]);
+		let m = real_cubic_roots(8.0, 8.0 * p, 2.0 * p * p - 8.0 * r, -q * q)
+			.into_iter().fold(f64::NEG_INFINITY, f64::max);
+		let m = numeric::newton(&resolvent, Complex::real(m), numeric::TOLERANCE, 0.0).re;
+		let s = (2.0 * m).sqrt();
+		solution.step(format!("m = {}", format_number(m)));
+		solution.step(format!("Hence (y² + p / 2 + m)² = (√(2m)y - q / 2√(2m))² with √(2m) = {}", format_number(s)));
+		let c1 = p / 2.0 + m - q / (2.0 * s);
+		let c2 = p / 2.0 + m + q / (2.0 * s);
+		solution.step(format!("y² + √(2m)y + p / 2 + m - q / 2√(2m) = 0 => Δ1 = {}", format_number(s * s - 4.0 * c1)));
+		solution.step(format!("y² - √(2m)y + p / 2 + m + q / 2√(2m) = 0 => Δ2 = {}", format_number(s * s - 4.0 * c2)));
+		for y in quadratic_roots(s, c1).into_iter().chain(quadratic_roots(-s, c2))
+		{
+			sol.push(y + shift);
 		}
-		_ => ()
 	}
+	let mut sol: Vec<Complex> = sol.into_iter().map(|root| numeric::clean_root(root, scaled) * Complex::real(unit)).collect();
+	sol.sort_by(|s1, s2| (!s1.is_real()).cmp(&!s2.is_real()).then(s1.re.total_cmp(&s2.re)).then(s1.im.total_cmp(&s2.im)));
+	solution.step(format!("When Y = 0, {} = {{S1, S2, S3, S4}} with", solution.unknown()));
+	for (i, y) in sol.iter().enumerate()
+	{
+		solution.step(format!("S{} = {}", i + 1, y));
+	}
+	solution.step(String::new());
+	solution.roots = sol;
 }
 
-fn solution_degree_2(coef: &Vec<f64>, delta: f64, positive: bool, cmp_token: &Comparison)
+// The roots are approximated when there is no closed form, reason explains why
+// ex: The polynomial degree is strictly greater than 4, there is no general closed form
+fn solution_numeric(solution: &mut Solution, poly: &Polynomial, reason: &str)
 {
-	let mut sol: Vec<f64> = vec![];
-	if delta > 0.0
+	solution.step(String::new());
+	solution.step(reason.to_string());
+	solution.step(format!("The roots are approximated with the Durand-Kerner method (tolerance: {:e}, at most {} iterations)",
+		numeric::TOLERANCE, numeric::MAX_ITERATIONS));
+	let result = numeric::durand_kerner(poly, numeric::TOLERANCE, numeric::MAX_ITERATIONS);
+	match result.converged
+	{
+		true => solution.step(format!("The method converged after {} iterations", result.iterations)),
+		false => solution.step(format!("Warning: the method did not converge after {} iterations", result.iterations))
+	}
+	solution.step(String::new());
+	solution.step(format!("When Y = 0, {} ≈ {{S1, ..., S{}}} with", solution.unknown(), solution.degree));
+	for (i, (root, residual)) in result.roots.iter().zip(&result.residuals).enumerate()
+	{
+		solution.step(format!("S{0} ≈ {1}\t(residual |P(S{0})| = {2:.2e})", i + 1, root, residual));
+	}
+	solution.step(String::new());
+	solution.step("These solutions are numeric approximations, not exact values".to_string());
+	solution.roots = result.roots;
+	solution.approximate = true;
+}
+
+// variable is the name of the unknown used in the explanation, method the one explained for the equations of degree 2
+// The steps are recorded at the given verbosity
+pub fn get_solution<T: Scalar>(poly: &Polynomial<T>, degree: u32, cmp_token: Comparison, variable: &str, method: Method, verbosity: Verbosity)
+	-> Result<Solution, ComputorError>
+{
+	if !poly.is_finite()
 	{
-		println!("The discriminant is strictly positive");
-		sol.push((-coef[1] - delta.sqrt()) / (2.0 * coef[0]));
-		sol.push((-coef[1] + delta.sqrt()) / (2.0 * coef[0]));
-		println!("When Y = 0, X = {{S1, S2}} with");
-		println!("S1 = (-b - √Δ) / 2a = {}", format_number(sol[0]));
-		println!("S2 = (-b + √Δ) / 2a = {}\n", format_number(sol[1]));
-		sol.sort_by(|a, b| a.partial_cmp(b).unwrap());
-		let sol = vec![format_number(sol[0]), format_number(sol[1])];
-		match cmp_token
+		return Err(ComputorError::InfiniteCoefficient);
+	}
+	let mut solution = Solution::new(variable, degree, cmp_token, verbosity);
+	match degree
+	{
+		0 => solution.set = solution_degree_0(poly.coefficient(0), &solution.comparison),
+		1 =>
 		{
-			Comparison::Equal =>
+			let (a, b) = (poly.coefficient(1), poly.coefficient(0));
+			for step in [String::new(), format!("a = {}", a.format()), format!("b = {}", b.format()), String::new()]
 			{
-				println!("Hence the solution is X = {{{}, {}}}", sol[0], sol[1]);
+				solution.step(step);
 			}
+			solution_degree_1(&mut solution, a, b);
+		}
+		// Equations of degree 2 and more are solved with f64, even in exact mode
+		_ => get_float_solution(&mut solution, &poly.to_f64(), method)
+	}
+	Ok(solution)
+}
 
-			Comparison::Greater =>
+fn get_float_solution(solution: &mut Solution, poly: &Polynomial, method: Method)
+{
+	let number = |n: f64| operand(format_number(n));
+	solution.step(String::new());
+	match solution.degree
+	{
+		2 =>
+		{
+			let (a, b, c) = (poly.coefficient(2), poly.coefficient(1), poly.coefficient(0));
+			solution.step(format!("a = {}", format_number(a)));
+			solution.step(format!("b = {}", format_number(b)));
+			solution.step(format!("c = {}", format_number(c)));
+			// Δ of the scaled coefficients, the one of a, b and c may not be representable
+			let (sa, sb, sc, factor) = scale(a, b, c);
+			let scaled = (sb * sb) - (4.0 * sa * sc);
+			let (delta, delta_factor) = match (scaled * factor * factor).is_finite()
+			{
+				true => (scaled * factor * factor, 1.0),
+				false => (scaled, factor)
+			};
+			if method == Method::Discriminant
 			{
-				match positive
+				let substituted = format!("{}² - 4 × {} × {}", number(b), number(a), number(c));
+				solution.formula("Δ = b² - 4ac", substituted, scaled_number(delta, delta_factor));
+				if !(b * b - 4.0 * a * c).is_finite()
 				{
-					true => println!("Hence the solution is X ∈ (-∞, {}) ∪ ({}, ∞)", sol[0], sol[1]),
-					false =>  println!("Hence the solution is X ∈ ({}, {})", sol[0], sol[1]),
+					solution.step(format!("b² - 4ac overflows, the roots are computed with a, b and c divided by {}", format_number(factor)));
 				}
 			}
-			Comparison::GreaterEq =>
+			solution.step(String::new());
+			solution.discriminant = Some(delta);
+			solution.discriminant_factor = delta_factor;
+			solution_degree_2(solution, (a, b, c), radical::quadratic_surd(a, b, c), method);
+		}
+		3 =>
+		{
+			let (a, b, c, d) = (poly.coefficient(3), poly.coefficient(2), poly.coefficient(1), poly.coefficient(0));
+			for (name, n) in [("a", a), ("b", b), ("c", c), ("d", d)]
 			{
-				match positive
-				{
-					true => println!("Hence the solution is X ∈ (-∞, {}] ∪ [{}, ∞)", sol[0], sol[1]),
-					false =>  println!("Hence the solution is X ∈ [{}, {}]", sol[0], sol[1]),
-				}
+				solution.step(format!("{} = {}", name, format_number(n)));
 			}
-			Comparison::Lower =>
+			// The coefficients are scaled, p, q and Δ don't change unless X is substituted
+			let (scaled, unit) = substitution(poly);
+			let (a, b, c, d) = match unit == 1.0
 			{
-				match positive
+				true => (a, b, c, d),
+				false =>
 				{
-					true =>  println!("Hence the solution is X ∈ ({}, {})", sol[0], sol[1]),
-					false => println!("Hence the solution is X ∈ (-∞, {}) ∪ ({}, ∞)", sol[0], sol[1]),
+					solution.step(format!("The roots are too far from 1 for p, q and Δ: X = {}U with", format_number(unit)));
+					solution.step(format!("{} = 0", scaled.display_with("U")));
+					(scaled.coefficient(3), scaled.coefficient(2), scaled.coefficient(1), scaled.coefficient(0))
 				}
+			};
+			let (sa, sb, sc, sd) = (scaled.coefficient(3), scaled.coefficient(2), scaled.coefficient(1), scaled.coefficient(0));
+			let p = (3.0 * sa * sc - sb * sb) / (3.0 * sa * sa);
+			let q = (2.0 * sb * sb * sb - 9.0 * sa * sb * sc + 27.0 * sa * sa * sd) / (27.0 * sa * sa * sa);
+			let delta = (q * q) / 4.0 + (p * p * p) / 27.0;
+			if !(p.is_finite() && q.is_finite() && delta.is_finite())
+			{
+				return solution_numeric(solution, poly, "p, q or Δ can't be represented, Cardano's method can't be used");
 			}
-			Comparison::LowerEq =>
+			solution.formula("p = (3ac - b²) / 3a²", format!("(3 × {0} × {2} - {1}²) / (3 × {0}²)", number(a), number(b), number(c)), format_number(p));
+			solution.formula("q = (2b³ - 9abc + 27a²d) / 27a³",
+				format!("(2 × {1}³ - 9 × {0} × {1} × {2} + 27 × {0}² × {3}) / (27 × {0}³)", number(a), number(b), number(c), number(d)), format_number(q));
+			solution.formula("Δ = q² / 4 + p³ / 27", format!("{}² / 4 + {}³ / 27", number(q), number(p)), format_number(delta));
+			solution.step(String::new());
+			// Δ of X is Δ of U × (unit³)²
+			solution.discriminant = Some(delta);
+			solution.discriminant_factor = unit * unit * unit;
+			solution_degree_3(solution, (sa, sb, sc, sd), p, q, delta, unit, &scaled);
+			if solution.roots.iter().any(|root| !root.is_finite())
 			{
-				match positive
+				solution.discriminant = None;
+				return solution_numeric(solution, poly, "The closed form gives roots that can't be represented");
+			}
+		}
+		4 =>
+		{
+			let (a, b, c, d, e) = (poly.coefficient(4), poly.coefficient(3), poly.coefficient(2),
+				poly.coefficient(1), poly.coefficient(0));
+			for (name, n) in [("a", a), ("b", b), ("c", c), ("d", d), ("e", e)]
+			{
+				solution.step(format!("{} = {}", name, format_number(n)));
+			}
+			// The coefficients are scaled, p, q and r don't change unless X is substituted
+			let (scaled, unit) = substitution(poly);
+			let (a, b, c, d, e) = match unit == 1.0
+			{
+				true => (a, b, c, d, e),
+				false =>
 				{
-					true =>  println!("Hence the solution is X ∈ [{}, {}]", sol[0], sol[1]),
-					false =>   println!("Hence the solution is X ∈ (-∞, {}] ∪ [{}, ∞)", sol[0], sol[1]),
+					solution.step(format!("The roots are too far from 1 for p, q and r: X = {}U with", format_number(unit)));
+					solution.step(format!("{} = 0", scaled.display_with("U")));
+					(scaled.coefficient(4), scaled.coefficient(3), scaled.coefficient(2), scaled.coefficient(1), scaled.coefficient(0))
 				}
+			};
+			let (sa, sb, sc, sd, se) = (scaled.coefficient(4), scaled.coefficient(3), scaled.coefficient(2),
+				scaled.coefficient(1), scaled.coefficient(0));
+			let p = (8.0 * sa * sc - 3.0 * sb * sb) / (8.0 * sa * sa);
+			let q = (sb * sb * sb - 4.0 * sa * sb * sc + 8.0 * sa * sa * sd) / (8.0 * sa * sa * sa);
+			let r = (16.0 * sa * sb * sb * sc - 3.0 * sb * sb * sb * sb - 64.0 * sa * sa * sb * sd + 256.0 * sa * sa * sa * se)
+				/ (256.0 * sa * sa * sa * sa);
+			if !(p.is_finite() && q.is_finite() && r.is_finite())
+			{
+				return solution_numeric(solution, poly, "p, q or r can't be represented, Ferrari's method can't be used");
+			}
+			solution.formula("p = (8ac - 3b²) / 8a²", format!("(8 × {0} × {2} - 3 × {1}²) / (8 × {0}²)", number(a), number(b), number(c)), format_number(p));
+			solution.formula("q = (b³ - 4abc + 8a²d) / 8a³",
+				format!("({1}³ - 4 × {0} × {1} × {2} + 8 × {0}² × {3}) / (8 × {0}³)", number(a), number(b), number(c), number(d)), format_number(q));
+			solution.formula("r = (16ab²c - 3b⁴ - 64a²bd + 256a³e) / 256a⁴",
+				format!("(16 × {0} × {1}² × {2} - 3 × {1}⁴ - 64 × {0}² × {1} × {3} + 256 × {0}³ × {4}) / (256 × {0}⁴)", number(a), number(b), number(c), number(d), number(e)),
+				format_number(r));
+			solution.step(String::new());
+			solution_degree_4(solution, (sa, sb, sc, sd), p, q, r, unit, &scaled);
+			if solution.roots.iter().any(|root| !root.is_finite())
+			{
+				return solution_numeric(solution, poly, "The closed form gives roots that can't be represented");
 			}
-			_ => ()
+		}
+		_ =>
+		{
+			// The numeric method explains itself from the start
+			solution.steps.clear();
+			solution_numeric(solution, poly, "The polynomial degree is strictly greater than 4, there is no general closed form")
 		}
 	}
-	else if delta == 0.0
+	if solution.degree >= 3
+	{
+		real_solution_set(solution, poly);
+	}
+}
+
+impl Solution
+{
+	// The answer without the explanation steps
+	// ex: The solution is X = {-2, 2}
+	pub fn conclusion(&self) -> String
+	{
+		self.conclusion_after(false)
+	}
+
+	// hence is true when the conclusion follows the explanation steps
+	fn conclusion_after(&self, hence: bool) -> String
 	{
-		println!("The discriminant is null");
-		sol.push(-coef[1] / (2.0 * coef[0]));
-		let sol = format_number(sol[0]);
-		println!("When Y = 0, X = -b / 2a = {}", sol);
-		match cmp_token
+		let set = match &self.set
 		{
-			Comparison::Equal =>
+			Some(set) => set,
+			None => return String::new()
+		};
+		let x = self.unknown();
+		if self.comparison == Comparison::Equal && self.has_complex_roots()
+		{
+			let real = match set
 			{
-				println!("Hence the solution is X = {}", sol);
-			}
+				SolutionSet::Values(_) => format!("In ℝ, the solution is {} = {}", x, set),
+				_ => "In ℝ, there is no solution".to_string()
+			};
+			let mut all: Vec<String> = self.roots.iter().map(|s| format!("{}", s)).collect();
+			all.dedup();
+			return format!("{}\nIn ℂ, the solution is {} = {{{}}}", real, x, all.join(", "));
+		}
+		let the = if hence { "Hence the" } else { "The" };
+		let shown = self.exact_root.as_ref().and_then(|root| exact_set(set, root, "∞")).unwrap_or_else(|| set.to_string());
+		match set
+		{
+			SolutionSet::Empty if hence => "Hence there is no solution in ℝ".to_string(),
+			SolutionSet::Empty => "There is no solution in ℝ".to_string(),
+			SolutionSet::Values(_) => format!("{} solution is {} = {}", the, x, shown),
+			_ => format!("{} solution is {} ∈ {}", the, x, shown)
+		}
+	}
+}
 
-			Comparison::Greater =>
+// The solution set of an equation of degree 1 written with its exact root, None for the other sets
+// ex: (7/3, ∞) where the set is (2.33, ∞)
+fn exact_set(set: &SolutionSet, root: &str, infinity: &str) -> Option<String>
+{
+	match set
+	{
+		SolutionSet::Values(values) if values.len() == 1 => Some(root.to_string()),
+		SolutionSet::Intervals(intervals) if intervals.len() == 1 =>
+		{
+			let low = match intervals[0].low
+			{
+				Bound::Open(_) => format!("({}", root),
+				Bound::Closed(_) => format!("[{}", root),
+				Bound::Infinite => format!("(-{}", infinity)
+			};
+			let high = match intervals[0].high
 			{
-				match positive
+				Bound::Open(_) => format!("{})", root),
+				Bound::Closed(_) => format!("{}]", root),
+				Bound::Infinite => format!("{})", infinity)
+			};
+			Some(format!("{}, {}", low, high))
+		}
+		_ => None
+	}
+}
+
+impl fmt::Display for Solution
+{
+	// The explanation steps then the conclusion
+	// ex:	...
+	//		Hence the solution is X ∈ (-∞, -2) ∪ (2, ∞)
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		for step in self.steps.lines()
+		{
+			writeln!(f, "{}", step)?;
+		}
+		write!(f, "{}", self.conclusion_after(!self.steps.is_empty()))
+	}
+}
+
+impl Latex for Solution
+{
+	// The discriminant, the formulas of the roots and the solution set, one line each
+	// ex:	\Delta = b^{2} - 4ac = 16 \\
+	//		x_{1} = \frac{-b - \sqrt{\Delta}}{2a} = -2 \\
+	//		...
+	fn latex(&self) -> String
+	{
+		let number = |n: f64| latex_number(&format_number(n));
+		let root = |i: usize| self.roots.get(i).map_or(String::new(), |s| s.latex());
+		let x = &self.variable;
+		let mut lines: Vec<String> = vec![];
+		let exact_root = self.exact_root.as_ref().map(|root| latex_number(root));
+		match (self.degree, self.discriminant, &exact_root)
+		{
+			(0, ..) => (),
+			(1, _, Some(exact)) => lines.push(format!("{} = -\\frac{{b}}{{a}} = {}", x, exact)),
+			(1, ..) => lines.push(format!("{} = -\\frac{{b}}{{a}} = {}", x, root(0))),
+			(2, Some(delta), _) =>
+			{
+				let delta_text = match self.discriminant_factor == 1.0
+				{
+					true => number(delta),
+					false => format!("({})^{{2}} \\times {}", number(self.discriminant_factor), number(delta))
+				};
+				lines.push(format!("\\Delta = b^{{2}} - 4ac = {}", delta_text));
+				if delta > 0.0
 				{
-					true => println!("Hence the solution is X ∈ (-∞, {0}) ∪ ({0}, ∞)", sol),
-					false =>  println!("Hence there is no solution in ℝ"),
+					lines.push(format!("{}_{{1}} = \\frac{{-b - \\sqrt{{\\Delta}}}}{{2a}} = {}", x, root(0)));
+					lines.push(format!("{}_{{2}} = \\frac{{-b + \\sqrt{{\\Delta}}}}{{2a}} = {}", x, root(1)));
 				}
-			}
-			Comparison::GreaterEq =>
-			{
-				match positive
+				else if delta == 0.0
 				{
-					true => println!("Hence the solution is X ∈ (-∞, {0}] ∪ [{0}, ∞)", sol),
-					false =>  println!("Hence the solution is X = {}", sol),
+					lines.push(format!("{}_{{0}} = -\\frac{{b}}{{2a}} = {}", x, root(0)));
 				}
-			}
-			Comparison::Lower =>
-			{
-				match positive
+				else
 				{
-					true =>  println!("Hence there is no solution in ℝ"),
-					false => println!("Hence the solution is X ∈ (-∞, {0}) ∪ ({0}, ∞)", sol),
+					lines.push(format!("{}_{{1}} = \\frac{{-b - i\\sqrt{{|\\Delta|}}}}{{2a}} = {}", x, root(0)));
+					lines.push(format!("{}_{{2}} = \\frac{{-b + i\\sqrt{{|\\Delta|}}}}{{2a}} = {}", x, root(1)));
 				}
 			}
-			Comparison::LowerEq =>
+			_ =>
 			{
-				match positive
+				if let Some(delta) = self.discriminant
+				{
+					let delta_text = match self.discriminant_factor == 1.0
+					{
+						true => number(delta),
+						false => format!("({})^{{2}} \\times {}", number(self.discriminant_factor), number(delta))
+					};
+					lines.push(format!("\\Delta = \\frac{{q^{{2}}}}{{4}} + \\frac{{p^{{3}}}}{{27}} = {}", delta_text));
+				}
+				let equal = if self.approximate { "\\approx" } else { "=" };
+				for (i, s) in self.roots.iter().enumerate()
 				{
-					true =>  println!("Hence the solution is X = {}", sol),
-					false =>   println!("Hence the solution is X ∈ (-∞, {0}] ∪ [{0}, ∞)", sol),
+					lines.push(format!("{}_{{{}}} {} {}", x, i + 1, equal, s.latex()));
 				}
 			}
-			_ => ()
 		}
-	}
-	else
-	{
-		println!("The discriminant is strictly negative");
-		sol.push((-coef[1] - abs(delta).sqrt()) / (2.0 * coef[0]));
-        sol.push((-coef[1] + abs(delta).sqrt()) / (2.0 * coef[0]));
-		println!("When Y = 0, X = {{S1, S2}} with");
-		println!("S1 = (-b - √Δ) / 2a = {}i", format_number(sol[0]));
-		println!("S2 = (-b + √Δ) / 2a = {}i\n", format_number(sol[1]));
-		sol.sort_by(|a, b| a.partial_cmp(b).unwrap());
-		let sol = vec![format_number(sol[0]), format_number(sol[1])];
-		match cmp_token
+		match &self.set
 		{
-			Comparison::Equal =>
-			{
-				println!("In ℝ, there is no solution");
-				println!("In ℂ, the solution is X = {{{}i, {}i}}", sol[0], sol[1]);
-			}
-
-			Comparison::Greater | Comparison::GreaterEq =>
+			Some(set) if self.comparison == Comparison::Equal && self.has_complex_roots() =>
 			{
-				match positive
-				{
-					true => println!("The solution is X ∈ ℝ"),
-					false =>  println!("There is no solution in ℝ")
-				}
+				let mut all: Vec<String> = self.roots.iter().map(|s| s.latex()).collect();
+				all.dedup();
+				lines.push(format!("\\text{{In }} \\mathbb{{R}}: {} \\in {}", x, set.latex()));
+				lines.push(format!("\\text{{In }} \\mathbb{{C}}: {} \\in \\{{{}\\}}", x, all.join(", ")));
 			}
-			Comparison::Lower | Comparison::LowerEq =>
+			Some(set) =>
 			{
-				match positive
+				let shown = exact_root.as_ref().and_then(|root| exact_set(set, root, "\\infty"));
+				match (set, shown)
 				{
-					true =>  println!("There is no solution in ℝ"),
-					false => println!("The solution is X ∈ ℝ"),
+					(SolutionSet::Values(values), None) if values.len() == 1 => lines.push(format!("{} = {}", x, number(values[0]))),
+					(SolutionSet::Values(_), Some(root)) => lines.push(format!("{} = {}", x, root)),
+					(_, shown) => lines.push(format!("{} \\in {}", x, shown.unwrap_or_else(|| set.latex())))
 				}
 			}
-			_ => ()
+			None => ()
 		}
+		lines.join(" \\\\\n")
 	}
 }
 
-pub fn get_solution(coef: &Vec<f64>, degree: u8, cmp_token: Comparison)
+#[cfg(test)]
+mod tests
 {
-	if coef.iter().any(|x| x.is_infinite())
+	use super::*;
+
+	// The roots of the equation with these coefficients, lowest degree first
+	fn roots(coefficients: Vec<f64>) -> Vec<Complex>
 	{
-		println!("The solution can't be found because one of the terms is infinite");
-		return
+		let poly = Polynomial::from_coefficients(coefficients);
+		get_solution(&poly, poly.degree(), Comparison::Equal, "x", Method::Discriminant, Verbosity::Quiet).unwrap().roots
 	}
-	match degree
+
+	fn assert_roots(found: Vec<Complex>, expected: &[Complex])
 	{
-		0 => solution_degree_0(coef[2], &cmp_token),
-		1 =>
+		assert_eq!(found.len(), expected.len(), "{:?}", found);
+		for (root, expected) in found.iter().zip(expected)
 		{
-			println!("\na = {}\nb = {}\n", format_number(coef[1]), format_number(coef[2]));
-			solution_degree_1(-coef[2] / coef[1], coef[1] > 0.0, &cmp_token);
+			assert!((root.re - expected.re).abs() < 1e-9 && (root.im - expected.im).abs() < 1e-9, "{:?} != {:?}", found, expected);
 		}
-		_ =>
+	}
+
+	#[test]
+	fn cubic_cardano()
+	{
+		// x³ + 1 = 0
+		let half = 3.0_f64.sqrt() / 2.0;
+		assert_roots(roots(vec![1.0, 0.0, 0.0, 1.0]), &[Complex::real(-1.0), Complex::new(0.5, half), Complex::new(0.5, -half)]);
+	}
+
+	#[test]
+	fn cubic_trigonometric_roots_are_clean()
+	{
+		// x³ - x = 0, the middle root is exactly 0
+		let found = roots(vec![0.0, -1.0, 0.0, 1.0]);
+		assert_roots(found.clone(), &[Complex::real(1.0), Complex::real(0.0), Complex::real(-1.0)]);
+		assert_eq!(found[1], Complex::real(0.0));
+	}
+
+	#[test]
+	fn cubic_null_discriminant()
+	{
+		// (x - 1)²(x + 2) = 0
+		assert_roots(roots(vec![2.0, -3.0, 0.0, 1.0]), &[Complex::real(-2.0), Complex::real(1.0), Complex::real(1.0)]);
+		// (x - 2)³ = 0
+		assert_roots(roots(vec![-8.0, 12.0, -6.0, 1.0]), &[Complex::real(2.0); 3]);
+	}
+
+	#[test]
+	fn cubic_small_distinct_roots()
+	{
+		// (x - 0.01)(x - 0.02)(x - 0.03) = 0 has a tiny but not null discriminant
+		assert_roots(roots(vec![-6e-6, 1.1e-3, -0.06, 1.0]), &[Complex::real(0.03), Complex::real(0.02), Complex::real(0.01)]);
+		assert_roots(roots(vec![-6e-9, 1.1e-5, -6e-3, 1.0]), &[Complex::real(3e-3), Complex::real(2e-3), Complex::real(1e-3)]);
+	}
+
+	#[test]
+	fn cubic_far_roots()
+	{
+		// x³ - 10¹¹⁰x = 0, p³ would overflow without X = sU
+		let found = roots(vec![0.0, -1e110, 0.0, 1.0]);
+		assert_eq!(found.len(), 3, "{:?}", found);
+		for (root, expected) in found.iter().zip([1e55, 0.0, -1e55])
 		{
-			println!("\na = {}\nb = {}\nc = {}", format_number(coef[0]), format_number(coef[1]), format_number(coef[2]));
-			let delta = (coef[1] * coef[1]) - (4.0 * coef[0] * coef[2]);
-			println!("Δ = {}\n", format_number(delta));
-			solution_degree_2(coef, delta, coef[0] > 0.0, &cmp_token);
+			assert!(root.im == 0.0 && abs(root.re - expected) <= 1e-12 * 1e55, "{:?}", found);
 		}
+		// 10²⁰⁰x³ + x + 1 = 0, the roots are about 10^(-200 / 3)
+		let poly = Polynomial::from_coefficients(vec![1.0, 1.0, 0.0, 1e200]);
+		let found = roots(poly.coefficients().to_vec());
+		assert_eq!(found.len(), 3, "{:?}", found);
+		for root in found
+		{
+			assert!(root.is_finite() && root.modulus() > 1e-68, "{:?}", root);
+			assert!(numeric::eval(&poly, root).modulus() < 1e-9, "{:?}", root);
+		}
+	}
+
+	#[test]
+	fn quartic_ferrari()
+	{
+		// (x - 1)(x - 2)(x + 3)(x + 4) = x⁴ + 4x³ - 7x² - 22x + 24 = 0
+		assert_roots(roots(vec![24.0, -22.0, -7.0, 4.0, 1.0]), &[Complex::real(-4.0), Complex::real(-3.0), Complex::real(1.0), Complex::real(2.0)]);
+	}
+
+	#[test]
+	fn quartic_biquadratic()
+	{
+		// x⁴ - 1 = 0
+		assert_roots(roots(vec![-1.0, 0.0, 0.0, 0.0, 1.0]),
+			&[Complex::real(-1.0), Complex::real(1.0), Complex::new(0.0, -1.0), Complex::new(0.0, 1.0)]);
+	}
+
+	#[test]
+	fn quartic_roots_are_clean()
+	{
+		// -x⁴ + x² - x = 0 has the root 0 exactly
+		let found = roots(vec![0.0, -1.0, 1.0, 0.0, -1.0]);
+		assert!(found.contains(&Complex::real(0.0)), "{:?}", found);
+	}
+
+	#[test]
+	fn quartic_far_roots()
+	{
+		// The roots of these equations are found and are finite, p, q and r would overflow without X = sU
+		for coefficients in [vec![1.0, 1.0, 0.0, 0.0, 1e100], vec![-1.0, 1.0, 0.0, 0.0, 1e80], vec![1.0, 1e100, 0.0, 0.0, 1.0]]
+		{
+			let poly = Polynomial::from_coefficients(coefficients);
+			let found = roots(poly.coefficients().to_vec());
+			assert_eq!(found.len(), 4, "{:?}", found);
+			for root in &found
+			{
+				let residual = numeric::verify_roots(&poly, &[*root]);
+				assert!(root.is_finite() && *root != Complex::real(0.0) && !residual[0].doubtful, "{:?}", found);
+			}
+		}
+		// x⁴ + 10¹⁰⁰x + 1 = 0 has the real roots -1e-100 and -∛10¹⁰⁰
+		let found = roots(vec![1.0, 1e100, 0.0, 0.0, 1.0]);
+		assert!(abs(found[0].re + 1e100_f64.cbrt()) <= 1e-12 * 1e34 && found[0].im == 0.0, "{:?}", found);
+		assert!(abs(found[1].re + 1e-100) <= 1e-112 && found[1].im == 0.0, "{:?}", found);
+	}
+
+	#[test]
+	fn close_real_roots_are_distinct()
+	{
+		let found: Vec<Complex> = [1.004, -5.0, 1.001, -7.0, -6.0].iter().map(|n| Complex::real(*n)).collect();
+		assert_eq!(real_roots(&found), vec![(-7.0, 1), (-6.0, 1), (-5.0, 1), (1.001, 1), (1.004, 1)]);
+		let double: Vec<Complex> = [1.0 + 1e-9, 1.0 - 1e-9, -2.0].iter().map(|n| Complex::real(*n)).collect();
+		assert_eq!(real_roots(&double), vec![(-2.0, 1), (1.0 - 1e-9, 2)]);
+	}
+
+	#[test]
+	fn quadratic_overflowing_discriminant()
+	{
+		// x² + 1e200x + 1 = 0
+		let poly = Polynomial::from_coefficients(vec![1.0, 1e200, 1.0]);
+		let solution = get_solution(&poly, 2, Comparison::Equal, "x", Method::Discriminant, Verbosity::Quiet).unwrap();
+		let (delta, factor) = (solution.discriminant.unwrap(), solution.discriminant_factor);
+		assert!(delta.is_finite() && factor > 1.0);
+		assert!(((delta.sqrt() * factor) / 1e200 - 1.0).abs() < 1e-12);
+		assert!(solution.errors.iter().all(|e| e.is_finite()), "{:?}", solution.errors);
+		assert_roots(solution.roots, &[Complex::real(-1e200), Complex::real(-1e-200)]);
 	}
 }
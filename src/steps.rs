@@ -0,0 +1,61 @@
+// How much of the explanation is recorded, a level also records the steps of the lower ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity
+{
+	// Only the solution
+	Quiet,
+
+	// The steps of the solver
+	#[default]
+	Normal,
+
+	// Also the reduction of the expression, each moved term and the values substituted in each formula
+	Explain
+}
+
+// The explanation recorded by the library as it goes, one line per step. An empty step is a blank line
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StepLog
+{
+	pub verbosity: Verbosity,
+	lines: Vec<String>
+}
+
+impl StepLog
+{
+	pub fn new(verbosity: Verbosity) -> Self
+	{
+		StepLog { verbosity, lines: vec![] }
+	}
+
+	// Keep the step when it is shown at the verbosity of the log
+	// ex: record(Verbosity::Explain, "Subtract 4 from both sides") is ignored at the Normal level
+	pub fn record(&mut self, level: Verbosity, step: String)
+	{
+		if level != Verbosity::Quiet && level <= self.verbosity
+		{
+			self.lines.push(step);
+		}
+	}
+
+	// True when the details of the Explain level are recorded
+	pub fn explains(&self) -> bool
+	{
+		self.verbosity == Verbosity::Explain
+	}
+
+	pub fn lines(&self) -> &[String]
+	{
+		&self.lines
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		self.lines.is_empty()
+	}
+
+	pub fn clear(&mut self)
+	{
+		self.lines.clear();
+	}
+}
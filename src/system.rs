@@ -0,0 +1,393 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::{ComputorError, Latex, Scalar, Token};
+use crate::abs;
+
+// An equation of a linear system, the unknowns are on the left side and the constant on the right side
+// ex: 2x + 3y = 5 => { coefficients: { x: 2, y: 3 }, constant: 5 }
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearEquation<T = f64>
+{
+	pub coefficients: BTreeMap<String, T>,
+	pub constant: T
+}
+
+// The value of an unknown when a system has infinitely many solutions
+#[derive(Debug, Clone, PartialEq)]
+pub enum Parametric<T = f64>
+{
+	// Any real number, the unknown is a parameter of the solutions
+	Free,
+
+	// A constant plus a multiple of some free unknowns given by their index
+	// ex: x = 1 - 2z with the unknowns [x, y, z] => Depends(1, [(2, -2)])
+	Depends(T, Vec<(usize, T)>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemSet<T = f64>
+{
+	Empty,
+
+	// The value of each unknown
+	Unique(Vec<T>),
+
+	// The value of each unknown as a function of the free ones
+	Infinite(Vec<Parametric<T>>)
+}
+
+// The result of a linear system solved by Gaussian elimination
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemSolution<T = f64>
+{
+	// The unknowns sorted by name, one column of the matrix each
+	pub unknowns: Vec<String>,
+
+	pub set: SystemSet<T>,
+
+	// The augmented matrix and the row operations applied to it, one line per step
+	pub steps: Vec<String>
+}
+
+// The terms (coefficient, degree, unknown) joined by their signs, the zero terms are skipped
+// ex: [(2, 1, x), (-1, 1, y), (0, 1, z)] => 2x - y
+fn signed_terms<T: Scalar>(terms: &[(T, u32, &str)], term: &impl Fn(&Token<T>, &str) -> String) -> String
+{
+	let mut to_display = String::new();
+	for (n, d, name) in terms
+	{
+		if *n == T::zero() { continue }
+		match (to_display.is_empty(), *n >= T::zero())
+		{
+			(true, _) => to_display.push_str(&term(&Token::Var((*n, *d)), name)),
+			(false, true) => to_display.push_str(&format!(" + {}", term(&Token::Var((*n, *d)), name))),
+			(false, false) => to_display.push_str(&format!(" - {}", term(&Token::Var((-*n, *d)), name)))
+		}
+	}
+	match to_display.is_empty()
+	{
+		true => term(&Token::Var((T::zero(), 0)), ""),
+		false => to_display
+	}
+}
+
+impl<T: Scalar> LinearEquation<T>
+{
+	fn write(&self, term: impl Fn(&Token<T>, &str) -> String) -> String
+	{
+		let terms: Vec<(T, u32, &str)> = self.coefficients.iter().map(|(name, n)| (*n, 1, name.as_str())).collect();
+		format!("{} = {}", signed_terms(&terms, &term), term(&Token::Var((self.constant, 0)), ""))
+	}
+}
+
+// The augmented matrix with its columns aligned, the constants are after the bar
+// A value null next to its size in sizes is the rounding error of a 0. ex: -5.55e-17 => 0
+// ex:	R1 | 2   3 | 5
+//		R2 | 1  -1 | 1
+fn matrix<T: Scalar>(rows: &[Vec<T>], sizes: &[Vec<f64>]) -> Vec<String>
+{
+	let cells: Vec<Vec<String>> = rows.iter().zip(sizes).map(|(row, sizes)| row.iter().zip(sizes).map(|(n, size)| match n.is_null(*size)
+	{
+		true => T::zero().format(),
+		false => n.format()
+	}).collect()).collect();
+	let columns = cells.first().map_or(0, |row| row.len());
+	let widths: Vec<usize> = (0..columns)
+		.map(|j| cells.iter().map(|row| row[j].chars().count()).max().unwrap_or(0))
+		.collect();
+	cells.iter().enumerate().map(|(i, row)|
+	{
+		let mut line = format!("R{} |", i + 1);
+		for (j, cell) in row.iter().enumerate()
+		{
+			if j + 1 == columns { line.push_str(" |") }
+			line.push_str(&format!(" {:>width$}", cell, width = widths[j]));
+		}
+		line
+	}).collect()
+}
+
+// Subtract factor times the row pivot from the row target, the coefficient of the column col becomes 0
+// Returns the operation. ex: R2 ← R2 - 0.50 × R1
+// sizes holds the largest number each value was computed from, it grows with the product subtracted
+fn eliminate<T: Scalar>(rows: &mut [Vec<T>], sizes: &mut [Vec<f64>], target: usize, pivot: usize, col: usize) -> String
+{
+	let factor = rows[target][col] / rows[pivot][col];
+	for j in 0..rows[target].len()
+	{
+		let n = rows[pivot][j];
+		rows[target][j] = rows[target][j] - factor * n;
+		sizes[target][j] = sizes[target][j].max(abs(factor.to_f64()) * sizes[pivot][j]);
+	}
+	rows[target][col] = T::zero();
+	let (sign, factor) = if factor < T::zero() { ("+", -factor) } else { ("-", factor) };
+	match factor == T::one()
+	{
+		true => format!("R{0} ← R{0} {1} R{2}", target + 1, sign, pivot + 1),
+		false => format!("R{0} ← R{0} {1} {2} × R{3}", target + 1, sign, factor.format(), pivot + 1)
+	}
+}
+
+impl<T: Scalar> SystemSolution<T>
+{
+	fn step(&mut self, step: String)
+	{
+		self.steps.push(step);
+	}
+
+	// The row operations then the matrix they give
+	fn operations(&mut self, operations: Vec<String>, rows: &[Vec<T>], sizes: &[Vec<f64>])
+	{
+		if operations.is_empty() { return }
+		self.step(String::new());
+		self.steps.extend(operations);
+		self.steps.extend(matrix(rows, sizes));
+	}
+}
+
+// Gaussian elimination with partial pivoting on the augmented matrix of the system,
+// the pivots are then scaled to 1 and the coefficients above them eliminated to read the solutions
+pub fn solve_system<T: Scalar>(equations: &[LinearEquation<T>]) -> Result<SystemSolution<T>, ComputorError>
+{
+	let unknowns: Vec<String> = equations.iter()
+		.flat_map(|equation| equation.coefficients.keys().cloned())
+		.collect::<BTreeSet<String>>()
+		.into_iter().collect();
+	let mut rows: Vec<Vec<T>> = equations.iter().map(|equation|
+	{
+		let mut row: Vec<T> = unknowns.iter().map(|name| *equation.coefficients.get(name).unwrap_or(&T::zero())).collect();
+		row.push(equation.constant);
+		row
+	}).collect();
+	if rows.iter().flatten().any(|n| !n.is_finite())
+	{
+		return Err(ComputorError::InfiniteCoefficient);
+	}
+	let (n, m) = (unknowns.len(), rows.len());
+	// Rounding errors leave tiny values instead of zeros after the row operations, a value is null
+	// next to the numbers it is computed from. ex: 1e-11 is not null in the row 1e-11x = 1e-11
+	let mut sizes: Vec<Vec<f64>> = rows.iter().map(|row| row.iter().map(|n| abs(n.to_f64())).collect()).collect();
+	let mut solution = SystemSolution { unknowns, set: SystemSet::Empty, steps: vec![String::new(), "Augmented matrix:".to_string()] };
+	solution.steps.extend(matrix(&rows, &sizes));
+
+	// The column of the pivot of each row
+	let mut pivots: Vec<usize> = vec![];
+	for col in 0..n
+	{
+		let row = pivots.len();
+		if row == m { break }
+
+		// Partial pivoting: the largest coefficient of the column limits the rounding errors,
+		// the first one is kept among equal ones so that the rows are not swapped needlessly
+		let best = (row + 1..m).fold(row, |best, i| match abs(rows[i][col].to_f64()) > abs(rows[best][col].to_f64())
+		{
+			true => i,
+			false => best
+		});
+		if rows[best][col].is_null(sizes[best][col]) { continue }
+		let mut operations: Vec<String> = vec![];
+		if best != row
+		{
+			rows.swap(row, best);
+			sizes.swap(row, best);
+			operations.push(format!("R{} ↔ R{}", row + 1, best + 1));
+		}
+		for i in row + 1..m
+		{
+			if rows[i][col].is_null(sizes[i][col]) { continue }
+			operations.push(eliminate(&mut rows, &mut sizes, i, row, col));
+		}
+		pivots.push(col);
+		solution.operations(operations, &rows, &sizes);
+	}
+
+	// A row left without any coefficient reads 0 = c
+	if let Some(i) = (pivots.len()..m).find(|i| !rows[*i][n].is_null(sizes[*i][n]))
+	{
+		solution.step(String::new());
+		solution.step(format!("R{} gives 0 = {}, which is impossible", i + 1, rows[i][n].format()));
+		solution.step(String::new());
+		return Ok(solution);
+	}
+
+	// Back substitution, each pivot becomes 1 and is the only coefficient of its column
+	for (row, col) in pivots.iter().cloned().enumerate().rev()
+	{
+		let mut operations: Vec<String> = vec![];
+		let pivot = rows[row][col];
+		if pivot != T::one()
+		{
+			for (value, size) in rows[row].iter_mut().zip(sizes[row].iter_mut())
+			{
+				*value = *value / pivot;
+				*size /= abs(pivot.to_f64());
+			}
+			rows[row][col] = T::one();
+			// ex: R2 ← R2 / (-5/2)
+			let divisor = match pivot < T::zero() || pivot.format().contains('/')
+			{
+				true => format!("({})", pivot.format()),
+				false => pivot.format()
+			};
+			operations.push(format!("R{0} ← R{0} / {1}", row + 1, divisor));
+		}
+		for i in 0..row
+		{
+			if rows[i][col].is_null(sizes[i][col]) { continue }
+			operations.push(eliminate(&mut rows, &mut sizes, i, row, col));
+		}
+		solution.operations(operations, &rows, &sizes);
+	}
+	solution.step(String::new());
+	solution.set = match pivots.len() == n
+	{
+		// The pivots are on the diagonal
+		true => SystemSet::Unique((0..n).map(|i| rows[i][n]).collect()),
+		false =>
+		{
+			solution.step(format!("The rank is {} for {} unknowns, the unknowns without a pivot are free", pivots.len(), n));
+			solution.step(String::new());
+			let mut values = vec![Parametric::Free; n];
+			for (row, col) in pivots.iter().enumerate()
+			{
+				let free: Vec<(usize, T)> = (0..n)
+					.filter(|j| !pivots.contains(j) && !rows[row][*j].is_null(sizes[row][*j]))
+					.map(|j| (j, -rows[row][j]))
+					.collect();
+				values[*col] = Parametric::Depends(rows[row][n], free);
+			}
+			SystemSet::Infinite(values)
+		}
+	};
+	Ok(solution)
+}
+
+impl<T: Scalar> SystemSolution<T>
+{
+	// The value of each unknown written with term
+	// ex: ["x = 1 - 2z", "y = 3 + z", "z ∈ ℝ"]
+	fn values(&self, term: impl Fn(&Token<T>, &str) -> String, real: &str) -> Vec<String>
+	{
+		match &self.set
+		{
+			SystemSet::Empty => vec![],
+			SystemSet::Unique(values) => self.unknowns.iter().zip(values)
+				.map(|(name, n)| format!("{} = {}", term(&Token::Var((T::one(), 1)), name), term(&Token::Var((*n, 0)), "")))
+				.collect(),
+			SystemSet::Infinite(values) => self.unknowns.iter().zip(values).map(|(name, value)|
+			{
+				let unknown = term(&Token::Var((T::one(), 1)), name);
+				match value
+				{
+					Parametric::Free => format!("{} {}", unknown, real),
+					Parametric::Depends(c, free) =>
+					{
+						let mut terms: Vec<(T, u32, &str)> = vec![(*c, 0, "")];
+						terms.extend(free.iter().map(|(j, n)| (*n, 1, self.unknowns[*j].as_str())));
+						format!("{} = {}", unknown, signed_terms(&terms, &term))
+					}
+				}
+			}).collect()
+		}
+	}
+
+	// The answer without the row operations
+	// ex: The solution is x = 2, y = 1
+	pub fn conclusion(&self) -> String
+	{
+		self.conclusion_after(false)
+	}
+
+	fn conclusion_after(&self, hence: bool) -> String
+	{
+		let values = self.values(|token, name| token.display_with(name), "∈ ℝ").join(", ");
+		match (&self.set, hence)
+		{
+			(SystemSet::Empty, true) => "Hence there is no solution".to_string(),
+			(SystemSet::Empty, false) => "There is no solution".to_string(),
+			(SystemSet::Unique(_), true) => format!("Hence the solution is {}", values),
+			(SystemSet::Unique(_), false) => format!("The solution is {}", values),
+			(SystemSet::Infinite(_), true) => format!("Hence there is an infinity of solutions: {}", values),
+			(SystemSet::Infinite(_), false) => format!("There is an infinity of solutions: {}", values)
+		}
+	}
+}
+
+impl<T: Scalar> fmt::Display for LinearEquation<T>
+{
+	// ex: 2x - y = 5
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		write!(f, "{}", self.write(|token, name| token.display_with(name)))
+	}
+}
+
+impl<T: Scalar> Latex for LinearEquation<T>
+{
+	fn latex(&self) -> String
+	{
+		self.write(|token, name| token.latex_with(name))
+	}
+}
+
+impl<T: Scalar> fmt::Display for SystemSolution<T>
+{
+	// The row operations then the conclusion
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		for step in &self.steps
+		{
+			writeln!(f, "{}", step)?;
+		}
+		write!(f, "{}", self.conclusion_after(true))
+	}
+}
+
+impl<T: Scalar> Latex for SystemSolution<T>
+{
+	// The value of each unknown, one line each
+	// ex:	x = 1 - 2z \\
+	//		z \in \mathbb{R}
+	fn latex(&self) -> String
+	{
+		match self.set
+		{
+			SystemSet::Empty => "\\text{No solution}".to_string(),
+			_ => self.values(|token, name| token.latex_with(name), "\\in \\mathbb{R}").join(" \\\\\n")
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+
+	// The system of equations given by their coefficients of x and y and their constant
+	fn solve(equations: &[(f64, f64, f64)]) -> SystemSolution
+	{
+		let equations: Vec<LinearEquation> = equations.iter().map(|(x, y, constant)| LinearEquation
+		{
+			coefficients: vec![("x".to_string(), *x), ("y".to_string(), *y)].into_iter().collect(),
+			constant: *constant
+		}).collect();
+		solve_system(&equations).unwrap()
+	}
+
+	#[test]
+	fn first_pivot_is_kept()
+	{
+		// x = 1; y = 2; x + y = 3 has its pivots in place
+		let solution = solve(&[(1.0, 0.0, 1.0), (0.0, 1.0, 2.0), (1.0, 1.0, 3.0)]);
+		assert!(!solution.steps.iter().any(|step| step.contains('↔')), "{:?}", solution.steps);
+	}
+
+	#[test]
+	fn null_cells_are_written_as_0()
+	{
+		// R3 is left with -5.55e-17 as its constant
+		let solution = solve(&[(0.1, 0.2, 0.3), (0.3, 0.1, 0.4), (1.0, -1.0, 0.0)]);
+		assert!(!solution.steps.iter().any(|step| step.contains("e-")), "{:?}", solution.steps);
+	}
+}